@@ -48,8 +48,40 @@ const main = async () => {
     "new",
     {
       asset_contract_address: FixedSizeBinary.fromHex(deployAssetsResult.evmAddress),
-      root: FixedSizeBinary.fromHex(setup.root),
-      campaign_end_time: campaignEndTime,
+      config: {
+        root: FixedSizeBinary.fromHex(setup.root),
+        campaign_start_time: 0n,
+        campaign_end_time: campaignEndTime,
+        hash_algo: { type: "Keccak256", value: undefined },
+        lenient_transfers: false,
+        sweep_delay: 0n,
+        num_leaves: BigInt(setup.leaves.length),
+        metadata_uri: "",
+        require_funding_before_start: false,
+        unit_value: [1n, 0n, 0n, 0n],
+        decimals_factor: [1n, 0n, 0n, 0n],
+        sweep_to_burn: false,
+        auto_extend_threshold_bps: 0,
+        auto_extend_duration: 0n,
+        expected_total: [0n, 0n, 0n, 0n],
+        max_distribution: totalAirdropAmount,
+        shard_roots: [],
+        pull_payments: false,
+        max_batch: 16,
+        require_allowlist: false,
+        allow_funding_after_end: false,
+        claim_cooldown: 0n,
+        root_provider: FixedSizeBinary.fromHex("0x0000000000000000000000000000000000000000"),
+        max_referrer_bps: 0,
+        fee_bps: 0,
+        max_fee_bps: 0,
+        grace_period: 0n,
+        late_penalty_bps: 0,
+        emit_remaining_balance: false,
+        end_time_timelock: 0n,
+        zero_hashes: [],
+        fund_deadline: 0n,
+      },
     },
   )
 
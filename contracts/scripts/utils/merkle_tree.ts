@@ -11,6 +11,19 @@ export type LeafData = {
   value: bigint
 }
 
+/**
+ * @notice Leaf type tags, the first byte of every tagged leaf preimage.
+ * @dev Mirrors the `LEAF_*` constants of the contract. Plain (address, uint256)
+ * leaves carry no tag; see `encodeLeaf`.
+ */
+export enum LeafTag {
+  Units = 0x01,
+  Share = 0x02,
+  Nonce = 0x03,
+  Escrow = 0x04,
+  MultiAsset = 0x05,
+}
+
 /**
  * @title MerkleTree
  * @notice Utility class for building and verifying Merkle proofs.
@@ -48,6 +61,25 @@ export class MerkleTree {
     return getBytes(keccak_256(encoded))
   }
 
+  /**
+   * @notice Encodes a leaf of any kind as `keccak256(tag || address || data)`.
+   * @dev Matches the contract's leaf type tags, so e.g. an escrow leaf can
+   * never be replayed as a nonce leaf. None of the contract's tagged leaf kinds
+   * has a 52-byte preimage, so none collides with an `encodeLeaf` leaf.
+   * @param tag Leaf type tag, one of `LeafTag`.
+   * @param recipient address.
+   * @param data Rest of the preimage, e.g. `nonce (8 bytes) || value (32 bytes)`.
+   * @return Encoded and hashed leaf (32 bytes).
+   */
+  public static encodeTaggedLeaf(tag: LeafTag, recipient: string, data: Uint8Array): Uint8Array {
+    const addr = getBytes(recipient) // 20 bytes
+    const encoded = new Uint8Array(1 + addr.length + data.length)
+    encoded[0] = tag
+    encoded.set(addr, 1)
+    encoded.set(data, 1 + addr.length)
+    return getBytes(keccak_256(encoded))
+  }
+
   /**
    * @notice Hashes two child nodes into a parent node.
    * @dev Computes `keccak256(left || right)`.
@@ -62,6 +94,19 @@ export class MerkleTree {
     return getBytes(keccak_256(concatenated))
   }
 
+  /**
+   * @notice Hashes two nodes into a parent node of a sorted-pair tree.
+   * @dev Puts the smaller hash first, compared byte by byte, so proofs need no
+   * index. Equal nodes hash as `a || b`, matching the contract's
+   * `verify_sorted_proof`.
+   * @param a First node hash (32 bytes).
+   * @param b Second node hash (32 bytes).
+   * @return Parent node hash (32 bytes).
+   */
+  public static hashSortedPair(a: Uint8Array, b: Uint8Array): Uint8Array {
+    return MerkleTree.compareBytes(a, b) <= 0 ? MerkleTree.hashPair(a, b) : MerkleTree.hashPair(b, a)
+  }
+
   /**
    * @notice Builds the Merkle tree from the leaves.
    * @dev Fills the `tree` array and computes the `root`.
@@ -118,6 +163,48 @@ export class MerkleTree {
     return proof
   }
 
+  /**
+   * @notice Returns a multiproof for several leaves, for `claim_multi`.
+   * @dev Walks the tree level by level: a node whose sibling is also being
+   * proven is paired with it (flag `true`), any other takes its sibling from
+   * the proof (flag `false`). Matches the contract's `verify_multiproof`.
+   * @param indices Leaf indices to prove, in ascending order.
+   * @return The shared proof and its flags.
+   */
+  public getMultiProof(indices: number[]): { proof: Uint8Array[]; proofFlags: boolean[] } {
+    for (let i = 0; i < indices.length; i++) {
+      const index = indices[i] as number
+      if (index < 0 || index >= this.leaves.length || (i > 0 && index <= (indices[i - 1] as number))) {
+        throw new Error("Leaf indices must be in bounds and strictly ascending.")
+      }
+    }
+
+    const proof: Uint8Array[] = []
+    const proofFlags: boolean[] = []
+    let nodes = indices.slice()
+
+    for (let level = 0; level < this.tree.length - 1; level++) {
+      const currentLevel = this.tree[level] as Uint8Array[]
+      const parents: number[] = []
+
+      for (let i = 0; i < nodes.length; i++) {
+        const index = nodes[i] as number
+        if (nodes[i + 1] === (index ^ 1)) {
+          proofFlags.push(true)
+          i++
+        } else {
+          proofFlags.push(false)
+          const sibling = currentLevel[index ^ 1] ?? currentLevel[index] // duplicate if odd
+          proof.push(sibling as Uint8Array)
+        }
+        parents.push(Math.floor(index / 2))
+      }
+      nodes = parents
+    }
+
+    return { proof, proofFlags }
+  }
+
   /**
    * @notice Verifies a Merkle proof for a leaf.
    * @dev Recomputes the root from the leaf, proof, and index.
@@ -148,6 +235,35 @@ export class MerkleTree {
     return MerkleTree.bytesEqual(computed, root)
   }
 
+  /**
+   * @notice Verifies a sorted-pair Merkle proof for a leaf.
+   * @dev Recomputes the root with `hashSortedPair`, ignoring leaf positions.
+   * @param leaf Leaf hash (32 bytes).
+   * @param proof Array of sibling hashes.
+   * @param root Expected Merkle root.
+   * @return True if the recomputed root matches.
+   */
+  public static verifySortedProof(leaf: Uint8Array, proof: Uint8Array[], root: Uint8Array): boolean {
+    const computed = proof.reduce((node, sibling) => MerkleTree.hashSortedPair(node, sibling), leaf)
+
+    return MerkleTree.bytesEqual(computed, root)
+  }
+
+  /**
+   * @notice Compares two byte arrays lexicographically.
+   * @dev Equivalent to comparing equal-length hashes as big-endian integers.
+   * @param a First byte array.
+   * @param b Second byte array.
+   * @return Negative if `a < b`, zero if equal, positive if `a > b`.
+   */
+  private static compareBytes(a: Uint8Array, b: Uint8Array): number {
+    const length = Math.min(a.length, b.length)
+    for (let i = 0; i < length; i++) {
+      if (a[i] !== b[i]) return (a[i] as number) - (b[i] as number)
+    }
+    return a.length - b.length
+  }
+
   /**
    * @notice Checks equality of two byte arrays.
    * @dev Used internally to compare computed root with expected root.
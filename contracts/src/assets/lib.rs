@@ -40,6 +40,23 @@ pub mod asset_hub_precompile {
         fn transferFrom(&mut self, from: Address, to: Address, value: U256) -> Result<bool, Error>;
     }
 
+    /// EIP-2612 `permit` extension, for assets that accept signed approvals.
+    #[ink::trait_definition]
+    pub trait Erc20Permit {
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        fn permit(
+            &mut self,
+            owner: Address,
+            spender: Address,
+            value: U256,
+            deadline: U256,
+            v: u8,
+            r: [u8; 32],
+            s: [u8; 32],
+        ) -> Result<(), Error>;
+    }
+
     #[ink(storage)]
     pub struct AssetHubPrecompile {
         asset_id: AssetId,
@@ -7,9 +7,28 @@ use ink::prelude::vec::Vec;
 use ink::Address;
 use ink::U256;
 use ink_e2e::ContractsBackend;
+use mock_asset::{MockAsset, MockAssetRef};
 
 type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+/// Current wall-clock time in milliseconds, the unit of `block_timestamp`.
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock is set before the UNIX epoch")
+        .as_millis() as u64
+}
+
+/// Wait until wall-clock time, and so the next block's timestamp, is past
+/// `timestamp`.
+fn wait_past(timestamp: u64) {
+    let now = now_ms();
+    if now <= timestamp {
+        // Leave a margin for the node's clock and block production.
+        std::thread::sleep(std::time::Duration::from_millis(timestamp - now + 2_000));
+    }
+}
+
 // Helper function to replicate the contract's hashing logic in the test environment.
 // This is crucial for creating the leaves and root correctly.
 fn hash_leaf(left: &[u8], right: &[u8]) -> [u8; 32] {
@@ -35,8 +54,10 @@ struct Setup {
     pub index_alice: u64,
     pub index_bob: u64,
     pub root: [u8; 32],
+    pub num_leaves: u64,
     pub creator: Address,
     pub asset_id: AssetId,
+    pub campaign_start_time: u64,
     pub campaign_end_time: u64,
 }
 
@@ -59,6 +80,7 @@ impl Setup {
 
         // Our tree has two leaves. The root is the hash of both leaves.
         let root = hash_leaf(&leaf_alice, &leaf_bob);
+        let num_leaves = 2;
 
         // To claim, Bob needs to provide the sibling leaf (Alice's) as proof.
         let proof_for_bob = vec![leaf_alice];
@@ -69,7 +91,10 @@ impl Setup {
         let creator =
             ink_e2e::address::<ink::env::DefaultEnvironment>(ink_e2e::Sr25519Keyring::Charlie);
         let asset_id = 1;
-        let campaign_end_time = 3;
+        // Block timestamps follow the node's wall clock, so the campaign
+        // must end relative to now for the constructor to accept it.
+        let campaign_start_time = 0;
+        let campaign_end_time = now_ms() + 60_000;
 
         Self {
             alice_account,
@@ -84,38 +109,469 @@ impl Setup {
             index_alice,
             index_bob,
             root,
+            num_leaves,
             creator,
             asset_id,
+            campaign_start_time,
             campaign_end_time,
         }
     }
+
+    /// Campaign settings for this tree, paying at most the total supply.
+    fn config(&self) -> CampaignConfig {
+        CampaignConfig {
+            root: self.root,
+            campaign_start_time: self.campaign_start_time,
+            campaign_end_time: self.campaign_end_time,
+            num_leaves: self.num_leaves,
+            max_distribution: self.total_supply,
+            ..CampaignConfig::default()
+        }
+    }
 }
 
 #[ink_e2e::test]
 async fn instantiate<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
     // given
+    let setup = Setup::new();
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+
+    // when
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await;
+
+    // then
+    assert!(contract.is_ok(), "{}", contract.err().unwrap());
+    let contract = contract.unwrap();
+    let call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let root = client
+        .call(&ink_e2e::charlie(), &call_builder.root())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(root, setup.root);
+
+    let owner = client
+        .call(&ink_e2e::charlie(), &call_builder.owner())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(owner, setup.creator);
+
+    let asset_valid = client
+        .call(&ink_e2e::charlie(), &call_builder.validate_asset())
+        .dry_run()
+        .await?
+        .return_value();
+    assert!(asset_valid);
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn validate_asset_rejects_plain_account<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    // when
+    let mut constructor = MerkleAirdropRef::new(setup.bob_account, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    // then
+    let asset_valid = client
+        .call(&ink_e2e::charlie(), &call_builder.validate_asset())
+        .dry_run()
+        .await?
+        .return_value();
+    assert!(!asset_valid);
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn instantiate_with_asset_id<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
     let assets_contract_code = client
         .upload("assets", &ink_e2e::charlie())
         .submit()
         .await
         .expect("assets upload failed");
 
+    // when
+    let mut constructor = MerkleAirdropRef::new_with_asset_id(
+        setup.asset_id,
+        assets_contract_code.code_hash,
+        setup.config(),
+    );
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    // then
+    let asset_id = client
+        .call(&ink_e2e::charlie(), &call_builder.asset_id())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(asset_id, setup.asset_id);
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn fund_twice_accumulates<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
     let setup = Setup::new();
 
     let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
     let asset_hub_contract = client
-        .instantiate(
-            "asset_hub_precompile",
-            &ink_e2e::charlie(),
-            &mut constructor,
-        )
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    let first_deposit = setup.total_supply / U256::from(2);
+    let second_deposit = setup.total_supply - first_deposit;
+
+    // when
+    let call = call_builder.fund(first_deposit);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+
+    let call = call_builder.top_up(second_deposit);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `top_up` failed")
+        .return_value();
+    assert!(result.is_ok(), "Top up failed");
+
+    // then
+    let contract_balance_call = assets_call_builder.balanceOf(contract.addr);
+    let contract_balance = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        contract_balance, setup.total_supply,
+        "Contract balance should equal the sum of both deposits"
+    );
+
+    let funded_by_call = call_builder.funded_by(setup.creator);
+    let funded_by = client
+        .call(&ink_e2e::charlie(), &funded_by_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(
+        funded_by, setup.total_supply,
+        "Creator's contribution should include both deposits"
+    );
+
+    let total_funded = client
+        .call(&ink_e2e::charlie(), &call_builder.total_funded())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(total_funded, setup.total_supply);
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn sweep_unclaimed_after_campaign_end<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let mut setup = Setup::new();
+    // Short window so the test can wait it out before sweeping.
+    setup.campaign_end_time = now_ms() + 15_000;
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+
+    wait_past(setup.campaign_end_time);
+
+    // when
+    let sweep_call = call_builder.sweep_unclaimed();
+    let result = client
+        .call(&ink_e2e::bob(), &sweep_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(result, Err(Error::Unauthorized), "Only the owner may sweep");
+
+    let result = client
+        .call(&ink_e2e::charlie(), &sweep_call)
+        .submit()
+        .await
+        .expect("Calling `sweep_unclaimed` failed")
+        .return_value();
+    assert!(result.is_ok(), "Sweep failed");
+
+    // then
+    let creator_balance_call = assets_call_builder.balanceOf(setup.creator);
+    let creator_balance = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        creator_balance,
+        setup.total_supply - setup.airdrop_amount_bob,
+        "Owner should receive everything Bob did not claim"
+    );
+
+    let contract_balance_call = assets_call_builder.balanceOf(contract.addr);
+    let contract_balance = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        contract_balance,
+        U256::zero(),
+        "Contract should be empty after the sweep"
+    );
+
+    let result = client
+        .call(&ink_e2e::charlie(), &sweep_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(
+        result,
+        Err(Error::NothingToSweep),
+        "Sweeping an empty contract should fail"
+    );
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn fund<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let creator_balance_call = assets_call_builder.balanceOf(setup.creator);
+    let creator_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_call = assets_call_builder.balanceOf(contract.addr);
+    let contract_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+
+    assert_eq!(creator_balance_before_fund, setup.total_supply);
+    assert_eq!(contract_balance_before_fund, U256::zero());
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    // when
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+    // then
+    let creator_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
         .submit()
         .await
-        .expect("failed");
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+
+    assert_eq!(
+        creator_balance_after_fund,
+        U256::zero(),
+        "Creator balance should be zero after funding"
+    );
+    assert_eq!(
+        contract_balance_after_fund, setup.total_supply,
+        "Contract balance should equal total supply after funding"
+    );
+
+    let solvent = client
+        .call(&ink_e2e::charlie(), &call_builder.assert_solvent())
+        .dry_run()
+        .await?
+        .return_value();
+    assert!(solvent, "A fully funded campaign should be solvent");
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn bob_claim<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
 
-    // Approve tokens BEFORE contract instantiation, so constructor can pull them in
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
     let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
-    let approve_call = assets_call_builder.approve(setup.creator, setup.total_supply);
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let creator_balance_call = assets_call_builder.balanceOf(setup.creator);
+    let creator_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_call = assets_call_builder.balanceOf(contract.addr);
+    let contract_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+
+    assert_eq!(creator_balance_before_fund, setup.total_supply);
+    assert_eq!(contract_balance_before_fund, U256::zero());
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
     let approve_result = client
         .call(&ink_e2e::charlie(), &approve_call)
         .submit()
@@ -125,655 +581,1284 @@ async fn instantiate<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
     assert!(approve_result.is_ok(), "Approve failed");
 
     // when
-    // let mut constructor = MerkleAirdropRef::new(
-    //     setup.asset_id,
-    //     assets_contract_code.code_hash,
-    //     setup.root,
-    //     setup.campaign_end_time,
-    //     setup.total_supply,
-    // );
-    // let contract = client
-    //     .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
-    //     .submit()
-    //     .await;
-
-    // // then
-    // assert!(contract.is_ok(), "{}", contract.err().unwrap());
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+    // then
+    let creator_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+
+    assert_eq!(
+        creator_balance_after_fund,
+        U256::zero(),
+        "Creator balance should be zero after funding"
+    );
+    assert_eq!(
+        contract_balance_after_fund, setup.total_supply,
+        "Contract balance should equal total supply after funding"
+    );
+
+    let bob_balance_call = assets_call_builder.balanceOf(setup.bob_account);
+    let bob_balance_before_claim = client
+        .call(&ink_e2e::bob(), &bob_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(bob_balance_before_claim, U256::zero());
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+    let bob_balance_after_claim = client
+        .call(&ink_e2e::bob(), &bob_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        bob_balance_after_claim, setup.airdrop_amount_bob,
+        "Bob's balance should equal his airdrop amount after claiming"
+    );
+    let contract_balance_after_claim = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        contract_balance_after_claim,
+        setup.total_supply - setup.airdrop_amount_bob,
+        "Contract balance should decrease by Bob's airdrop amount after he claims"
+    );
+
+    let via_airdrop = client
+        .call(
+            &ink_e2e::bob(),
+            &call_builder.asset_balance_of(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(via_airdrop, bob_balance_after_claim);
+    let remaining = client
+        .call(&ink_e2e::bob(), &call_builder.remaining_balance())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(remaining, contract_balance_after_claim);
+
+    let receipt = client
+        .call(
+            &ink_e2e::bob(),
+            &call_builder.claim_receipt(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value()
+        .expect("Bob should have a receipt");
+    assert_eq!(receipt.amount, setup.airdrop_amount_bob);
+    assert_eq!(receipt.index, setup.index_bob);
 
     Ok(())
 }
 
-// #[ink_e2e::test]
-// async fn fund<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
-//     // given
-//     let assets_contract_code = client
-//         .upload("assets", &ink_e2e::charlie())
-//         .submit()
-//         .await
-//         .expect("assets upload failed");
-
-//     let setup = Setup::new();
-//     let mut constructor = MerkleAirdropRef::new(
-//         setup.asset_id,
-//         assets_contract_code.code_hash,
-//         setup.root,
-//         setup.campaign_end_time,
-//         setup.total_supply,
-//     );
-//     let contract = client
-//         .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
-//         .submit()
-//         .await
-//         .expect("merkle_airdrop instantiate failed");
-//     let mut call_builder = contract.call_builder::<MerkleAirdrop>();
-
-//     let call = call_builder.asset_id();
-//     let asset_id = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `asset_id` failed")
-//         .return_value();
-
-//     let mut assets_call_builder = ink_e2e::create_call_builder::<AssetHubPrecompile>(asset_id);
-//     let creator_balance_call = assets_call_builder.balance_of(setup.creator);
-//     let creator_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_call = assets_call_builder.balance_of(contract.addr);
-//     let contract_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-
-//     assert_eq!(creator_balance_before_fund, setup.total_supply);
-//     assert_eq!(contract_balance_before_fund, U256::zero());
-
-//     let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
-//     let approve_result = client
-//         .call(&ink_e2e::charlie(), &approve_call)
-//         .submit()
-//         .await
-//         .expect("Calling `approve` failed")
-//         .return_value();
-//     assert!(approve_result.is_ok(), "Approve failed");
-
-//     // when
-//     let call = call_builder.fund(setup.total_supply);
-//     let result = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `fund` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Fund failed");
-//     // then
-//     let creator_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-
-//     assert_eq!(
-//         creator_balance_after_fund,
-//         U256::zero(),
-//         "Creator balance should be zero after funding"
-//     );
-//     assert_eq!(
-//         contract_balance_after_fund, setup.total_supply,
-//         "Contract balance should equal total supply after funding"
-//     );
-
-//     Ok(())
-// }
-
-// #[ink_e2e::test]
-// async fn bob_claim<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
-//     // given
-//     let assets_contract_code = client
-//         .upload("assets", &ink_e2e::charlie())
-//         .submit()
-//         .await
-//         .expect("assets upload failed");
-
-//     let setup = Setup::new();
-//     let mut constructor = MerkleAirdropRef::new(
-//         setup.asset_id,
-//         assets_contract_code.code_hash,
-//         setup.root,
-//         setup.campaign_end_time,
-//         setup.total_supply,
-//     );
-//     let contract = client
-//         .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
-//         .submit()
-//         .await
-//         .expect("merkle_airdrop instantiate failed");
-//     let mut call_builder = contract.call_builder::<MerkleAirdrop>();
-
-//     let call = call_builder.asset_id();
-//     let asset_id = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `asset_id` failed")
-//         .return_value();
-
-//     let mut assets_call_builder = ink_e2e::create_call_builder::<AssetHubPrecompile>(asset_id);
-//     let creator_balance_call = assets_call_builder.balance_of(setup.creator);
-//     let creator_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_call = assets_call_builder.balance_of(contract.addr);
-//     let contract_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-
-//     assert_eq!(creator_balance_before_fund, setup.total_supply);
-//     assert_eq!(contract_balance_before_fund, U256::zero());
-
-//     let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
-//     let approve_result = client
-//         .call(&ink_e2e::charlie(), &approve_call)
-//         .submit()
-//         .await
-//         .expect("Calling `approve` failed")
-//         .return_value();
-//     assert!(approve_result.is_ok(), "Approve failed");
-
-//     // when
-//     let call = call_builder.fund(setup.total_supply);
-//     let result = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `fund` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Fund failed");
-//     // then
-//     let creator_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-
-//     assert_eq!(
-//         creator_balance_after_fund,
-//         U256::zero(),
-//         "Creator balance should be zero after funding"
-//     );
-//     assert_eq!(
-//         contract_balance_after_fund, setup.total_supply,
-//         "Contract balance should equal total supply after funding"
-//     );
-
-//     let bob_balance_call = assets_call_builder.balance_of(setup.bob_account);
-//     let bob_balance_before_claim = client
-//         .call(&ink_e2e::bob(), &bob_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(bob_balance_before_claim, U256::zero());
-//     let call = call_builder.claim(
-//         setup.airdrop_amount_bob,
-//         setup.proof_for_bob.clone(),
-//         setup.index_bob,
-//     );
-//     let result = client
-//         .call(&ink_e2e::bob(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `claim` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Claim failed");
-//     let bob_balance_after_claim = client
-//         .call(&ink_e2e::bob(), &bob_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         bob_balance_after_claim, setup.airdrop_amount_bob,
-//         "Bob's balance should equal his airdrop amount after claiming"
-//     );
-//     let contract_balance_after_claim = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         contract_balance_after_claim,
-//         setup.total_supply - setup.airdrop_amount_bob,
-//         "Contract balance should decrease by Bob's airdrop amount after he claims"
-//     );
-
-//     Ok(())
-// }
-
-// #[ink_e2e::test]
-// async fn alice_claim<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
-//     // given
-//     let assets_contract_code = client
-//         .upload("assets", &ink_e2e::charlie())
-//         .submit()
-//         .await
-//         .expect("assets upload failed");
-
-//     let setup = Setup::new();
-//     let mut constructor = MerkleAirdropRef::new(
-//         setup.asset_id,
-//         assets_contract_code.code_hash,
-//         setup.root,
-//         setup.campaign_end_time,
-//         setup.total_supply,
-//     );
-//     let contract = client
-//         .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
-//         .submit()
-//         .await
-//         .expect("merkle_airdrop instantiate failed");
-//     let mut call_builder = contract.call_builder::<MerkleAirdrop>();
-
-//     let call = call_builder.asset_id();
-//     let asset_id = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `asset_id` failed")
-//         .return_value();
-
-//     let mut assets_call_builder = ink_e2e::create_call_builder::<AssetHubPrecompile>(asset_id);
-//     let creator_balance_call = assets_call_builder.balance_of(setup.creator);
-//     let creator_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_call = assets_call_builder.balance_of(contract.addr);
-//     let contract_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-
-//     assert_eq!(creator_balance_before_fund, setup.total_supply);
-//     assert_eq!(contract_balance_before_fund, U256::zero());
-
-//     let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
-//     let approve_result = client
-//         .call(&ink_e2e::charlie(), &approve_call)
-//         .submit()
-//         .await
-//         .expect("Calling `approve` failed")
-//         .return_value();
-//     assert!(approve_result.is_ok(), "Approve failed");
-
-//     // when
-//     let call = call_builder.fund(setup.total_supply);
-//     let result = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `fund` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Fund failed");
-//     // then
-//     let creator_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         creator_balance_after_fund,
-//         U256::zero(),
-//         "Creator balance should be zero after funding"
-//     );
-//     assert_eq!(
-//         contract_balance_after_fund, setup.total_supply,
-//         "Contract balance should equal total supply after funding"
-//     );
-//     let alice_balance_call = assets_call_builder.balance_of(setup.alice_account);
-//     let alice_balance_before_claim = client
-//         .call(&ink_e2e::alice(), &alice_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(alice_balance_before_claim, U256::zero());
-//     let call = call_builder.claim(
-//         setup.airdrop_amount_alice,
-//         setup.proof_for_alice.clone(),
-//         setup.index_alice,
-//     );
-//     let result = client
-//         .call(&ink_e2e::alice(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `claim` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Claim failed");
-//     let alice_balance_after_claim = client
-//         .call(&ink_e2e::alice(), &alice_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         alice_balance_after_claim, setup.airdrop_amount_alice,
-//         "Alice's balance should equal her airdrop amount after claiming"
-//     );
-//     let contract_balance_after_claim = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         contract_balance_after_claim,
-//         setup.total_supply - setup.airdrop_amount_alice,
-//         "Contract balance should decrease by Alice's airdrop amount after she claims"
-//     );
-
-//     Ok(())
-// }
-
-// #[ink_e2e::test]
-// async fn bob_and_alice_claim<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
-//     // given
-//     let assets_contract_code = client
-//         .upload("assets", &ink_e2e::charlie())
-//         .submit()
-//         .await
-//         .expect("assets upload failed");
-
-//     let setup = Setup::new();
-//     let mut constructor = MerkleAirdropRef::new(
-//         setup.asset_id,
-//         assets_contract_code.code_hash,
-//         setup.root,
-//         setup.campaign_end_time,
-//         setup.total_supply,
-//     );
-//     let contract = client
-//         .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
-//         .submit()
-//         .await
-//         .expect("merkle_airdrop instantiate failed");
-//     let mut call_builder = contract.call_builder::<MerkleAirdrop>();
-
-//     let call = call_builder.asset_id();
-//     let asset_id = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `asset_id` failed")
-//         .return_value();
-
-//     let mut assets_call_builder = ink_e2e::create_call_builder::<AssetHubPrecompile>(asset_id);
-//     let creator_balance_call = assets_call_builder.balance_of(setup.creator);
-//     let creator_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_call = assets_call_builder.balance_of(contract.addr);
-//     let contract_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-
-//     assert_eq!(creator_balance_before_fund, setup.total_supply);
-//     assert_eq!(contract_balance_before_fund, U256::zero());
-
-//     let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
-//     let approve_result = client
-//         .call(&ink_e2e::charlie(), &approve_call)
-//         .submit()
-//         .await
-//         .expect("Calling `approve` failed")
-//         .return_value();
-//     assert!(approve_result.is_ok(), "Approve failed");
-
-//     // when
-//     let call = call_builder.fund(setup.total_supply);
-//     let result = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `fund` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Fund failed");
-//     // then
-//     let creator_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_after_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         creator_balance_after_fund,
-//         U256::zero(),
-//         "Creator balance should be zero after funding"
-//     );
-//     assert_eq!(
-//         contract_balance_after_fund, setup.total_supply,
-//         "Contract balance should equal total supply after funding"
-//     );
-//     let bob_balance_call = assets_call_builder.balance_of(setup.bob_account);
-//     let bob_balance_before_claim = client
-//         .call(&ink_e2e::bob(), &bob_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(bob_balance_before_claim, U256::zero());
-//     let call = call_builder.claim(
-//         setup.airdrop_amount_bob,
-//         setup.proof_for_bob.clone(),
-//         setup.index_bob,
-//     );
-//     let result = client
-//         .call(&ink_e2e::bob(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `claim` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Claim failed");
-//     let bob_balance_after_claim = client
-//         .call(&ink_e2e::bob(), &bob_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         bob_balance_after_claim, setup.airdrop_amount_bob,
-//         "Bob's balance should equal his airdrop amount after claiming"
-//     );
-//     let contract_balance_after_bob_claim = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         contract_balance_after_bob_claim,
-//         setup.total_supply - setup.airdrop_amount_bob,
-//         "Contract balance should decrease by Bob's airdrop amount after he claims"
-//     );
-//     let alice_balance_call = assets_call_builder.balance_of(setup.alice_account);
-//     let alice_balance_before_claim = client
-//         .call(&ink_e2e::alice(), &alice_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(alice_balance_before_claim, U256::zero());
-//     let call = call_builder.claim(
-//         setup.airdrop_amount_alice,
-//         setup.proof_for_alice.clone(),
-//         setup.index_alice,
-//     );
-//     let result = client
-//         .call(&ink_e2e::alice(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `claim` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Claim failed");
-//     let alice_balance_after_claim = client
-//         .call(&ink_e2e::alice(), &alice_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         alice_balance_after_claim, setup.airdrop_amount_alice,
-//         "Alice's balance should equal her airdrop amount after claiming"
-//     );
-//     let contract_balance_after_alice_claim = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     assert_eq!(
-//         contract_balance_after_alice_claim,
-//         setup.total_supply - setup.airdrop_amount_bob - setup.airdrop_amount_alice,
-//         "Contract balance should decrease by Alice's airdrop amount after she claims"
-//     );
-//     Ok(())
-// }
-
-// #[ink_e2e::test]
-// async fn cannot_claim_twice<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
-//     // given
-//     let assets_contract_code = client
-//         .upload("assets", &ink_e2e::charlie())
-//         .submit()
-//         .await
-//         .expect("assets upload failed");
-
-//     let setup = Setup::new();
-//     let mut constructor = MerkleAirdropRef::new(
-//         setup.asset_id,
-//         assets_contract_code.code_hash,
-//         setup.root,
-//         setup.campaign_end_time,
-//         setup.total_supply,
-//     );
-//     let contract = client
-//         .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
-//         .submit()
-//         .await
-//         .expect("merkle_airdrop instantiate failed");
-//     let mut call_builder = contract.call_builder::<MerkleAirdrop>();
-
-//     let call = call_builder.asset_id();
-//     let asset_id = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `fund` failed")
-//         .return_value();
-
-//     let mut assets_call_builder = ink_e2e::create_call_builder::<AssetHubPrecompile>(asset_id);
-//     let creator_balance_call = assets_call_builder.balance_of(setup.creator);
-//     let creator_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &creator_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-//     let contract_balance_call = assets_call_builder.balance_of(contract.addr);
-//     let contract_balance_before_fund = client
-//         .call(&ink_e2e::charlie(), &contract_balance_call)
-//         .submit()
-//         .await
-//         .expect("Calling `balance_of` failed")
-//         .return_value();
-
-//     assert_eq!(creator_balance_before_fund, setup.total_supply);
-//     assert_eq!(contract_balance_before_fund, U256::zero());
-
-//     let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
-//     let approve_result = client
-//         .call(&ink_e2e::charlie(), &approve_call)
-//         .submit()
-//         .await
-//         .expect("Calling `approve` failed")
-//         .return_value();
-//     assert!(approve_result.is_ok(), "Approve failed");
-
-//     // when
-//     let call = call_builder.fund(setup.total_supply);
-//     let result = client
-//         .call(&ink_e2e::charlie(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `fund` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Fund failed");
-//     // then
-//     let call = call_builder.claim(
-//         setup.airdrop_amount_bob,
-//         setup.proof_for_bob.clone(),
-//         setup.index_bob,
-//     );
-//     let result = client
-//         .call(&ink_e2e::bob(), &call)
-//         .submit()
-//         .await
-//         .expect("Calling `claim` failed")
-//         .return_value();
-//     assert!(result.is_ok(), "Claim failed");
-
-//     let result = client.call(&ink_e2e::bob(), &call).dry_run().await?;
-//     assert!(result.is_err(), "Calling claim again should fail");
-
-//     Ok(())
-// }
+#[ink_e2e::test]
+async fn alice_claim<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let creator_balance_call = assets_call_builder.balanceOf(setup.creator);
+    let creator_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_call = assets_call_builder.balanceOf(contract.addr);
+    let contract_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+
+    assert_eq!(creator_balance_before_fund, setup.total_supply);
+    assert_eq!(contract_balance_before_fund, U256::zero());
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    // when
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+    // then
+    let creator_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        creator_balance_after_fund,
+        U256::zero(),
+        "Creator balance should be zero after funding"
+    );
+    assert_eq!(
+        contract_balance_after_fund, setup.total_supply,
+        "Contract balance should equal total supply after funding"
+    );
+    let alice_balance_call = assets_call_builder.balanceOf(setup.alice_account);
+    let alice_balance_before_claim = client
+        .call(&ink_e2e::alice(), &alice_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(alice_balance_before_claim, U256::zero());
+    let call = call_builder.claim(
+        setup.airdrop_amount_alice,
+        setup.proof_for_alice.clone(),
+        setup.index_alice,
+    );
+    let result = client
+        .call(&ink_e2e::alice(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+    let alice_balance_after_claim = client
+        .call(&ink_e2e::alice(), &alice_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        alice_balance_after_claim, setup.airdrop_amount_alice,
+        "Alice's balance should equal her airdrop amount after claiming"
+    );
+    let contract_balance_after_claim = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        contract_balance_after_claim,
+        setup.total_supply - setup.airdrop_amount_alice,
+        "Contract balance should decrease by Alice's airdrop amount after she claims"
+    );
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn bob_and_alice_claim<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let creator_balance_call = assets_call_builder.balanceOf(setup.creator);
+    let creator_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_call = assets_call_builder.balanceOf(contract.addr);
+    let contract_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+
+    assert_eq!(creator_balance_before_fund, setup.total_supply);
+    assert_eq!(contract_balance_before_fund, U256::zero());
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    // when
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+    // then
+    let creator_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_after_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        creator_balance_after_fund,
+        U256::zero(),
+        "Creator balance should be zero after funding"
+    );
+    assert_eq!(
+        contract_balance_after_fund, setup.total_supply,
+        "Contract balance should equal total supply after funding"
+    );
+    let bob_balance_call = assets_call_builder.balanceOf(setup.bob_account);
+    let bob_balance_before_claim = client
+        .call(&ink_e2e::bob(), &bob_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(bob_balance_before_claim, U256::zero());
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+    let bob_balance_after_claim = client
+        .call(&ink_e2e::bob(), &bob_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        bob_balance_after_claim, setup.airdrop_amount_bob,
+        "Bob's balance should equal his airdrop amount after claiming"
+    );
+    let contract_balance_after_bob_claim = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        contract_balance_after_bob_claim,
+        setup.total_supply - setup.airdrop_amount_bob,
+        "Contract balance should decrease by Bob's airdrop amount after he claims"
+    );
+    let alice_balance_call = assets_call_builder.balanceOf(setup.alice_account);
+    let alice_balance_before_claim = client
+        .call(&ink_e2e::alice(), &alice_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(alice_balance_before_claim, U256::zero());
+    let call = call_builder.claim(
+        setup.airdrop_amount_alice,
+        setup.proof_for_alice.clone(),
+        setup.index_alice,
+    );
+    let result = client
+        .call(&ink_e2e::alice(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+    let alice_balance_after_claim = client
+        .call(&ink_e2e::alice(), &alice_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        alice_balance_after_claim, setup.airdrop_amount_alice,
+        "Alice's balance should equal her airdrop amount after claiming"
+    );
+    let contract_balance_after_alice_claim = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    assert_eq!(
+        contract_balance_after_alice_claim,
+        setup.total_supply - setup.airdrop_amount_bob - setup.airdrop_amount_alice,
+        "Contract balance should decrease by Alice's airdrop amount after she claims"
+    );
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn anyone_can_claim_to_the_leaf_recipient<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed");
+
+    // when
+    // Charlie submits Bob's leaf; he is neither Bob nor his delegate.
+    let call = call_builder.claim_to_leaf(
+        setup.bob_account,
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim_to_leaf` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+
+    // then
+    let bob_balance = client
+        .call(
+            &ink_e2e::bob(),
+            &assets_call_builder.balanceOf(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(bob_balance, setup.airdrop_amount_bob);
+
+    let charlie_balance = client
+        .call(
+            &ink_e2e::charlie(),
+            &assets_call_builder.balanceOf(setup.creator),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(charlie_balance, U256::zero());
+
+    let is_claimed = client
+        .call(&ink_e2e::bob(), &call_builder.is_claimed(setup.bob_account))
+        .dry_run()
+        .await?
+        .return_value();
+    assert!(is_claimed, "Bob should be marked claimed");
+
+    // Bob's own claim is now a double claim.
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(result, Err(Error::AlreadyClaimed));
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn cannot_claim_twice<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let creator_balance_call = assets_call_builder.balanceOf(setup.creator);
+    let creator_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &creator_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+    let contract_balance_call = assets_call_builder.balanceOf(contract.addr);
+    let contract_balance_before_fund = client
+        .call(&ink_e2e::charlie(), &contract_balance_call)
+        .submit()
+        .await
+        .expect("Calling `balanceOf` failed")
+        .return_value();
+
+    assert_eq!(creator_balance_before_fund, setup.total_supply);
+    assert_eq!(contract_balance_before_fund, U256::zero());
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    // when
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+    // then
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(
+        result,
+        Err(Error::AlreadyClaimed),
+        "Calling claim again should fail"
+    );
+
+    let claimed_count = client
+        .call(&ink_e2e::bob(), &call_builder.claimed_count())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(claimed_count, 1, "A repeated claim should not be counted");
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn claim_is_not_reentrant<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    // An asset whose `transfer` calls back into the airdrop to claim Bob's
+    // leaf again before the first claim has returned.
+    let mut constructor = MockAssetRef::new(setup.total_supply);
+    let asset_contract = client
+        .instantiate("mock_asset", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("mock_asset instantiate failed");
+    let mut assets_call_builder = asset_contract.call_builder::<MockAsset>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+
+    // Bob lets the asset claim for him, so the re-entrant call gets past the
+    // delegate check and is left to the double-claim protection.
+    let call = call_builder.set_delegate(asset_contract.addr);
+    client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `set_delegate` failed");
+
+    let arm_call = assets_call_builder.arm_reentry(
+        contract.addr,
+        setup.bob_account,
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    client
+        .call(&ink_e2e::charlie(), &arm_call)
+        .submit()
+        .await
+        .expect("Calling `arm_reentry` failed");
+
+    // when
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claim failed");
+
+    // then
+    let attempted = client
+        .call(
+            &ink_e2e::charlie(),
+            &assets_call_builder.reentry_attempted(),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert!(
+        attempted,
+        "Transfer should have called back into the airdrop"
+    );
+
+    let reentry_error = client
+        .call(&ink_e2e::charlie(), &assets_call_builder.reentry_error())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(
+        reentry_error,
+        Error::AlreadyClaimed as u8,
+        "Re-entrant claim should see the leaf as already claimed"
+    );
+
+    let bob_balance = client
+        .call(
+            &ink_e2e::bob(),
+            &assets_call_builder.balanceOf(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(
+        bob_balance, setup.airdrop_amount_bob,
+        "Bob should be paid exactly once"
+    );
+
+    let claimed_amount = client
+        .call(
+            &ink_e2e::bob(),
+            &call_builder.claimed_amount(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(claimed_amount, setup.airdrop_amount_bob);
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn refused_transfer_is_owed_until_retried<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    // An asset that refuses to pay Bob, e.g. because he is on its own blocklist.
+    let mut constructor = MockAssetRef::new(setup.total_supply);
+    let asset_contract = client
+        .instantiate("mock_asset", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("mock_asset instantiate failed");
+    let mut assets_call_builder = asset_contract.call_builder::<MockAsset>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed");
+
+    let block_call = assets_call_builder.set_blocked(setup.bob_account, true);
+    client
+        .call(&ink_e2e::charlie(), &block_call)
+        .submit()
+        .await
+        .expect("Calling `set_blocked` failed");
+
+    // when
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+
+    // then
+    assert!(result.is_ok(), "A verified claim should stand");
+
+    let is_claimed = client
+        .call(&ink_e2e::bob(), &call_builder.is_claimed(setup.bob_account))
+        .dry_run()
+        .await?
+        .return_value();
+    assert!(is_claimed, "Bob should be marked claimed");
+
+    let owed_call = call_builder.owed(setup.bob_account);
+    let owed = client
+        .call(&ink_e2e::bob(), &owed_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(owed, setup.airdrop_amount_bob);
+
+    let bob_balance_call = assets_call_builder.balanceOf(setup.bob_account);
+    let bob_balance = client
+        .call(&ink_e2e::bob(), &bob_balance_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(bob_balance, U256::zero());
+
+    // Retrying while the asset still refuses Bob keeps the payout owed.
+    let retry_call = call_builder.retry_claim();
+    let result = client
+        .call(&ink_e2e::bob(), &retry_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(result, Err(Error::TransferFailed));
+
+    // Once the asset accepts Bob again, the retry pays without a proof.
+    let unblock_call = assets_call_builder.set_blocked(setup.bob_account, false);
+    client
+        .call(&ink_e2e::charlie(), &unblock_call)
+        .submit()
+        .await
+        .expect("Calling `set_blocked` failed");
+
+    let result = client
+        .call(&ink_e2e::bob(), &retry_call)
+        .submit()
+        .await
+        .expect("Calling `retry_claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Retry failed");
+
+    let bob_balance = client
+        .call(&ink_e2e::bob(), &bob_balance_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(bob_balance, setup.airdrop_amount_bob);
+
+    let owed = client
+        .call(&ink_e2e::bob(), &owed_call)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(owed, U256::zero());
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn lenient_mode_accepts_tokens_that_return_nothing<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = MockAssetRef::new(setup.total_supply);
+    let asset_contract = client
+        .instantiate("mock_asset", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("mock_asset instantiate failed");
+    let mut assets_call_builder = asset_contract.call_builder::<MockAsset>();
+
+    let mut constructor = MerkleAirdropRef::new(
+        asset_contract.addr,
+        CampaignConfig {
+            lenient_transfers: true,
+            ..setup.config()
+        },
+    );
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed");
+
+    let silent_call = assets_call_builder.set_silent(true);
+    client
+        .call(&ink_e2e::charlie(), &silent_call)
+        .submit()
+        .await
+        .expect("Calling `set_silent` failed");
+
+    // A refusal still surfaces as a revert, so Alice is blocked to check it
+    // is not mistaken for a payout.
+    let block_call = assets_call_builder.set_blocked(setup.alice_account, true);
+    client
+        .call(&ink_e2e::charlie(), &block_call)
+        .submit()
+        .await
+        .expect("Calling `set_blocked` failed");
+
+    // when
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+
+    let call = call_builder.claim(
+        setup.airdrop_amount_alice,
+        setup.proof_for_alice.clone(),
+        setup.index_alice,
+    );
+    let alice_result = client
+        .call(&ink_e2e::alice(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+
+    // then
+    assert!(result.is_ok(), "Claim against a silent token failed");
+    assert!(alice_result.is_ok(), "A verified claim should stand");
+
+    let bob_balance = client
+        .call(
+            &ink_e2e::bob(),
+            &assets_call_builder.balanceOf(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(bob_balance, setup.airdrop_amount_bob);
+
+    let bob_owed = client
+        .call(&ink_e2e::bob(), &call_builder.owed(setup.bob_account))
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(bob_owed, U256::zero());
+
+    let alice_owed = client
+        .call(&ink_e2e::alice(), &call_builder.owed(setup.alice_account))
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(alice_owed, setup.airdrop_amount_alice);
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn refused_referral_bonus_is_owed_to_the_referrer<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+    let referrer = ink_e2e::address::<ink::env::DefaultEnvironment>(ink_e2e::Sr25519Keyring::Dave);
+
+    let mut constructor = MockAssetRef::new(setup.total_supply);
+    let asset_contract = client
+        .instantiate("mock_asset", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("mock_asset instantiate failed");
+    let mut assets_call_builder = asset_contract.call_builder::<MockAsset>();
+
+    let mut constructor = MerkleAirdropRef::new(
+        asset_contract.addr,
+        CampaignConfig {
+            max_referrer_bps: 1_000,
+            ..setup.config()
+        },
+    );
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed");
+
+    let block_call = assets_call_builder.set_blocked(referrer, true);
+    client
+        .call(&ink_e2e::charlie(), &block_call)
+        .submit()
+        .await
+        .expect("Calling `set_blocked` failed");
+
+    // when
+    let call = call_builder.claim_with_referrer(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+        referrer,
+        1_000,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .submit()
+        .await
+        .expect("Calling `claim_with_referrer` failed")
+        .return_value();
+
+    // then
+    assert!(
+        result.is_ok(),
+        "A refused bonus should not revert the claim"
+    );
+
+    let bob_balance = client
+        .call(
+            &ink_e2e::bob(),
+            &assets_call_builder.balanceOf(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(bob_balance, setup.airdrop_amount_bob);
+
+    let referrer_owed = client
+        .call(&ink_e2e::bob(), &call_builder.owed(referrer))
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(referrer_owed, setup.airdrop_amount_bob / U256::from(10));
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn reversed_claim_can_be_claimed_again<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = MockAssetRef::new(setup.total_supply);
+    let asset_contract = client
+        .instantiate("mock_asset", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("mock_asset instantiate failed");
+    let mut assets_call_builder = asset_contract.call_builder::<MockAsset>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed");
+
+    // Bob's claim is refused by the asset and kept owed.
+    let block_call = assets_call_builder.set_blocked(setup.bob_account, true);
+    client
+        .call(&ink_e2e::charlie(), &block_call)
+        .submit()
+        .await
+        .expect("Calling `set_blocked` failed");
+
+    let claim_call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    client
+        .call(&ink_e2e::bob(), &claim_call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed");
+
+    // when
+    let reverse_call = call_builder.reverse_claim(setup.bob_account);
+    let result = client
+        .call(&ink_e2e::charlie(), &reverse_call)
+        .dry_run()
+        .await?
+        .return_value();
+
+    // then
+    assert_eq!(result, Err(Error::PayoutOutstanding));
+
+    // Once Bob has been paid and has sent the tokens back, the claim can be
+    // reversed and made again.
+    let unblock_call = assets_call_builder.set_blocked(setup.bob_account, false);
+    client
+        .call(&ink_e2e::charlie(), &unblock_call)
+        .submit()
+        .await
+        .expect("Calling `set_blocked` failed");
+
+    client
+        .call(&ink_e2e::bob(), &call_builder.retry_claim())
+        .submit()
+        .await
+        .expect("Calling `retry_claim` failed");
+
+    let return_call = assets_call_builder.transfer(contract.addr, setup.airdrop_amount_bob);
+    client
+        .call(&ink_e2e::bob(), &return_call)
+        .submit()
+        .await
+        .expect("Calling `transfer` failed");
+
+    let result = client
+        .call(&ink_e2e::charlie(), &reverse_call)
+        .submit()
+        .await
+        .expect("Calling `reverse_claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Reversal failed");
+
+    let total_claimed = client
+        .call(&ink_e2e::bob(), &call_builder.total_claimed())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(total_claimed, U256::zero());
+
+    let result = client
+        .call(&ink_e2e::bob(), &claim_call)
+        .submit()
+        .await
+        .expect("Calling `claim` failed")
+        .return_value();
+    assert!(result.is_ok(), "Claiming again failed");
+
+    let bob_balance = client
+        .call(
+            &ink_e2e::bob(),
+            &assets_call_builder.balanceOf(setup.bob_account),
+        )
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(bob_balance, setup.airdrop_amount_bob);
+
+    let total_claimed = client
+        .call(&ink_e2e::bob(), &call_builder.total_claimed())
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(total_claimed, setup.airdrop_amount_bob);
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn fund_with_permit_requires_permit_support<Client: E2EBackend>(
+    mut client: Client,
+) -> E2EResult<()> {
+    // given
+    let setup = Setup::new();
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    // when
+    let call =
+        call_builder.fund_with_permit(setup.total_supply, U256::MAX, 27, [0x11; 32], [0x22; 32]);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .dry_run()
+        .await?
+        .return_value();
+
+    // then
+    assert_eq!(
+        result,
+        Err(Error::PermitFailed),
+        "An asset without `permit` should be reported, not trap"
+    );
+
+    Ok(())
+}
+
+#[ink_e2e::test]
+async fn claim_after_campaign_end_fails<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    // given
+    let mut setup = Setup::new();
+    // Block timestamps follow the node's wall clock, so time is advanced by
+    // waiting out a short window rather than setting it.
+    setup.campaign_end_time = now_ms() + 15_000;
+
+    let mut constructor = AssetHubPrecompileRef::new(setup.asset_id);
+    let asset_hub_contract = client
+        .instantiate("assets", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("assets instantiate failed");
+    let mut assets_call_builder = asset_hub_contract.call_builder::<AssetHubPrecompile>();
+
+    let mut constructor = MerkleAirdropRef::new(asset_hub_contract.addr, setup.config());
+    let contract = client
+        .instantiate("merkle_airdrop", &ink_e2e::charlie(), &mut constructor)
+        .submit()
+        .await
+        .expect("merkle_airdrop instantiate failed");
+    let mut call_builder = contract.call_builder::<MerkleAirdrop>();
+
+    let approve_call = assets_call_builder.approve(contract.addr, setup.total_supply);
+    let approve_result = client
+        .call(&ink_e2e::charlie(), &approve_call)
+        .submit()
+        .await
+        .expect("Calling `approve` failed")
+        .return_value();
+    assert!(approve_result.is_ok(), "Approve failed");
+
+    let call = call_builder.fund(setup.total_supply);
+    let result = client
+        .call(&ink_e2e::charlie(), &call)
+        .submit()
+        .await
+        .expect("Calling `fund` failed")
+        .return_value();
+    assert!(result.is_ok(), "Fund failed");
+
+    wait_past(setup.campaign_end_time);
+
+    // when
+    let call = call_builder.claim(
+        setup.airdrop_amount_bob,
+        setup.proof_for_bob.clone(),
+        setup.index_bob,
+    );
+    let result = client
+        .call(&ink_e2e::bob(), &call)
+        .dry_run()
+        .await?
+        .return_value();
+
+    // then
+    assert_eq!(
+        result,
+        Err(Error::ClaimPeriodOver),
+        "Claiming after the campaign end should fail"
+    );
+
+    Ok(())
+}
@@ -11,66 +11,872 @@
 /// - Efficient distribution: only the root of the Merkle tree is stored.
 /// - Trustless claims: recipients self-claim with Merkle proofs.
 /// - Double-claim protection: each recipient can only claim once.
-/// - Claim window: contract owner can configure an end time.
+/// - Claim window: contract owner can configure a start and an end time.
 /// - Sweep: owner can recover unclaimed tokens after the campaign ends.
+/// - Refund: funders can reclaim their pro-rata share of leftovers instead.
 ///
 /// ## Storage
 /// - `asset_contract`: reference to an ERC20-compatible token contract.
 /// - `root`: Merkle root committing to `(address, amount)` pairs.
+/// - `num_leaves`: number of leaves committed to by `root`.
+/// - `hash_algo`: hashing algorithm the Merkle tree was built with.
+/// - `lenient_transfers`: accept token calls that succeed without returning a value.
+/// - `sweep_delay`: grace period after the campaign ends before sweeping is allowed.
+/// - `metadata_uri`: off-chain pointer (IPFS CID / URL) describing the campaign.
 /// - `claimed`: mapping to track which addresses have claimed.
+/// - `claimed_bitmap`: packed bitmap of claimed leaf indices, 256 per slot.
+/// - `delegates`: address each recipient authorized to claim on their behalf.
+/// - `claimed_at` / `claimed_index`: when and from which leaf each address claimed.
 /// - `owner`: deployer of the contract, authorized for admin actions.
+/// - `campaign_start_time`: block timestamp from which claiming is allowed.
 /// - `campaign_end_time`: block timestamp after which claiming stops.
+/// - `require_funding_before_start`: reject deposits once claiming has opened.
+/// - `contributions`: running total deposited by each funder.
+/// - `total_contributions`: sum of all outstanding funder contributions.
+/// - `unit_value`: token amount per unit for `(address, units)` leaves.
+/// - `total_claimed`: sum of all amounts paid out by claims.
+/// - `claimed_nonces`: which `(address, nonce)` entries have claimed.
+/// - `funded`: whether any deposit has been made, freezing `asset_contract`.
+/// - `decimals_factor`: multiplier from committed amounts to the asset's smallest unit.
+/// - `sweep_to_burn`: whether sweeps burn the residual instead of returning it.
+/// - `claimed_amounts`: cumulative amount each address has been paid by claims.
+/// - `auto_extend_threshold_bps`: claimed share below which the first sweep extends the campaign.
+/// - `auto_extend_duration`: how long an automatic extension reopens claims for.
+/// - `expected_total`: total amount the tree allocates, for the auto-extension threshold and funding deadline.
+/// - `auto_extended`: whether the one automatic extension has been used.
+/// - `max_distribution`: upper bound on the sum of all claim payouts.
+/// - `shard_roots`: roots and leaf counts of additional shard trees, addressed by position.
+/// - `tree_claimed`: which addresses have claimed from each shard tree.
+/// - `pull_payments`: whether claims are credited for a later `withdraw` instead of pushed.
+/// - `pending_withdrawals`: claimed amounts awaiting `withdraw`.
+/// - `reserved`: sum of `pending_withdrawals`, `escrows` and `owed`, owed to claimants and never swept.
+/// - `max_batch`: most entries accepted by a single `claim_batch` call.
+/// - `used_signatures`: signed claim digests already consumed by `claim_with_sig`.
+/// - `require_allowlist`: whether claims also require the recipient to be allowlisted.
+/// - `allowlist`: recipients registered as allowed to claim when `require_allowlist` is set.
+/// - `proof_digests`: hash of the proof each address claimed with, for forensics.
+/// - `claimed_count`: number of leaves in the main tree claimed so far.
+/// - `allow_funding_after_end`: whether deposits are accepted after the campaign has ended.
+/// - `claim_cooldown`: minimum time between two payouts to the same recipient.
+/// - `last_payout_at`: when each recipient was last paid, for `claim_cooldown`.
+/// - `root_index`: sequence number of the current root, bumped on every root change.
+/// - `recent_roots`: the last `ROOT_HISTORY_SIZE` replaced roots, for `claim_against_root`.
+/// - `operator`: account allowed to pause and update the root alongside the owner.
+/// - `paused`: whether the owner or operator has suspended claims.
+/// - `receipts`: receipt of each address's claim from the main tree.
+/// - `payout_overrides`: where each recipient's claims are paid instead, set by the owner.
+/// - `initialized`: whether the campaign has been configured, for `new_uninitialized` deployments.
+/// - `vested_claimed`: committed amount of each nonce entry already paid by `claim_all_vested`.
+/// - `root_provider`: contract the Merkle root is read from, if not the local `root`.
+/// - `root_synced_at`: block in which `root` was last cached from `root_provider`.
+/// - `total_funded`: sum of every deposit ever made, for reconciliation.
+/// - `max_referrer_bps`: cap on the referral bonus, in basis points of the claim.
+/// - `fee_bps`: share of each payout withheld as a claim fee, in basis points.
+/// - `max_fee_bps`: ceiling on `fee_bps`, fixed at deployment.
+/// - `escrows`: amounts claimed into escrow and when each unlocks.
+/// - `claims_enabled`: whether the owner has opened claims, separate from `paused`.
+/// - `root_log`: the last `ROOT_LOG_SIZE` roots, from the initial one on, for audits,
+///   keyed by `root_index % ROOT_LOG_SIZE`.
+/// - `funding_finalized`: whether the owner has closed funding so share claims can begin.
+/// - `cancelled`: whether the campaign has been cancelled.
+/// - `grace_period`: how long after the end late claims are accepted at a penalty.
+/// - `late_penalty_bps`: share of each late payout withheld during the grace period.
+/// - `emit_remaining_balance`: whether `Claimed` events report the contract's remaining balance.
+/// - `claim_times`: timestamps of the last `CLAIM_LOG_SIZE` claims, for `claims_in_range`.
+/// - `claim_log_len`: number of claims ever logged to `claim_times`.
+/// - `end_time_timelock`: delay before a queued end time change can be applied.
+/// - `pending_end_time`: end time queued by `queue_end_time_change`, or zero.
+/// - `end_time_effective_at`: when `pending_end_time` may be applied.
+/// - `zero_hashes`: empty subtree roots per level, for sparse tree proofs.
+/// - `transfer_lock`: set while a transfer to the asset contract is in flight.
+/// - `owed`: claim payouts whose transfer failed, awaiting `retry_claim`.
+/// - `fund_deadline`: time by which `expected_total` must be funded, or the campaign is cancelled.
+/// - `tree_receipts`: receipt of each address's claim from each shard tree.
+/// - `asset_receipts`: per-asset outcome of each multi-asset claim.
+/// - `multi_claim_indices`: leaf indices of each `claim_multi` claim, for reversal.
+/// - `owed_assets`: refused multi-asset payouts of other assets, per recipient and asset.
+/// - `reserved_assets`: sum of `owed_assets` per asset, never swept.
 pub use self::merke_airdrop::*;
 
+/// Interface of a contract that publishes a Merkle root for airdrops to
+/// share, see the `root_provider` constructor argument.
+#[ink::trait_definition]
+pub trait RootProvider {
+    /// Get the current Merkle root.
+    #[ink(message)]
+    fn root(&self) -> [u8; 32];
+}
+
 #[ink::contract]
 mod merke_airdrop {
-    use assets::asset_hub_precompile::{AssetHubPrecompileRef, Erc20};
-    use assets::AssetId;
+    use super::RootProvider;
+    use assets::asset_hub_precompile::prefixed_address;
+    use assets::asset_hub_precompile::{AssetHubPrecompileRef, Erc20, Erc20Permit};
+    use assets::{AssetId, PRECOMPILE_INDEX};
+    use ink::abi::Sol;
+    use ink::codegen::TraitCallBuilder;
     use ink::env::hash_bytes;
+    use ink::env::DefaultEnvironment;
     use ink::env::{
         call::FromAddr,
-        hash::{HashOutput, Keccak256},
+        hash::{Blake2x256, CryptoHash, HashOutput, Keccak256, Sha2x256},
     };
+    use ink::prelude::string::String;
     use ink::prelude::vec::Vec;
-    use ink::storage::Mapping;
-    use ink::U256;
+    use ink::storage::{Lazy, Mapping};
+    use ink::{ToAddr, H160, H256, U256};
+
+    /// Hashing algorithm used for the leaves and nodes of the Merkle tree.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum HashAlgo {
+        /// `keccak256`, matching Ethereum tooling.
+        #[default]
+        Keccak256,
+        /// `blake2b-256`, matching Substrate tooling.
+        Blake2x256,
+        /// `sha256`, matching Bitcoin-style tooling and bridges.
+        Sha2x256,
+    }
+
+    /// Overall campaign status returned by [`MerkleAirdrop::state`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum CampaignState {
+        /// The claim window has not opened yet.
+        NotStarted,
+        /// Claims are being accepted.
+        Active,
+        /// The claim window has closed.
+        Ended,
+        /// The window is open, but claims are paused or disabled.
+        Paused,
+        /// The campaign has been cancelled and will not pay claims.
+        Cancelled,
+    }
+
+    /// Result of a [`MerkleAirdrop::try_claim`] attempt.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum ClaimOutcome {
+        /// Tokens were paid out to the caller.
+        Claimed,
+        /// The caller or the leaf index has already claimed.
+        AlreadyClaimed,
+        /// The Merkle proof did not validate against the stored root, or the
+        /// leaf index is outside the tree.
+        InvalidProof,
+        /// The claim window has not opened yet.
+        NotStarted,
+        /// The claim window has closed.
+        PeriodOver,
+        /// The campaign requires an allowlist the caller is not on.
+        NotAllowed,
+    }
+
+    /// Diagnosis returned by [`MerkleAirdrop::why_invalid`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum RejectionReason {
+        /// The claim would currently succeed.
+        Valid,
+        /// The claim window is not open.
+        OutsideWindow,
+        /// The recipient or the leaf index has already claimed.
+        AlreadyClaimed,
+        /// The leaf index is not below `num_leaves`.
+        IndexOutOfRange,
+        /// The proof has fewer siblings than the tree has levels.
+        ProofTooShort,
+        /// The proof is long enough but does not hash up to the stored root.
+        RootMismatch,
+        /// The campaign requires an allowlist the recipient is not on.
+        NotAllowed,
+        /// The leaf is valid but commits to a zero amount.
+        ZeroAmount,
+        /// The leaf is valid but the campaign is paused.
+        Paused,
+        /// The leaf is valid but the owner has disabled claims.
+        ClaimsDisabled,
+        /// The leaf is valid but the campaign has been cancelled.
+        Cancelled,
+        /// The leaf is valid but the recipient's claim cooldown is running.
+        Cooldown,
+        /// The root could not be read from the campaign's `root_provider`.
+        RootUnavailable,
+    }
+
+    /// One entry of a [`MerkleAirdrop::claim_batch`] call.
+    #[derive(Debug, Clone, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct BatchClaim {
+        /// Address committed to in the leaf, and paid the tokens.
+        pub recipient: Address,
+        /// Claim amount, as committed in the tree.
+        pub value: U256,
+        /// Merkle proof for `(recipient, value)`.
+        pub proof: Vec<[u8; 32]>,
+        /// Leaf index in the Merkle tree.
+        pub index: u64,
+    }
+
+    /// One entry of a [`MerkleAirdrop::claim_all_vested`] call.
+    #[derive(Debug, Clone, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct NonceClaim {
+        /// Claim amount for this entry, as committed in the tree.
+        pub value: U256,
+        /// Entry number distinguishing the recipient's allocations.
+        pub nonce: u64,
+        /// Merkle proof for `(recipient, nonce, value)`.
+        pub proof: Vec<[u8; 32]>,
+        /// Leaf index in the Merkle tree.
+        pub index: u64,
+    }
+
+    /// Record of a claim, returned by [`MerkleAirdrop::claim_receipt`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ClaimReceipt {
+        /// Tokens paid out, after scaling by `decimals_factor` and net of the
+        /// claim fee and any late penalty, like
+        /// [`MerkleAirdrop::claimed_amount`].
+        pub amount: U256,
+        /// Block timestamp of the claim.
+        pub timestamp: u64,
+        /// Leaf index the claim was made from.
+        pub index: u64,
+        /// Part of the claim withheld as the claim fee and any late penalty,
+        /// so `amount + withheld` is what the claim added to `total_claimed`.
+        pub withheld: U256,
+    }
+
+    /// One asset of a [`MerkleAirdrop::claim_multi_asset`] leaf.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct AssetAmount {
+        /// Asset Hub asset id, paid through its ERC20 precompile.
+        pub id: AssetId,
+        /// Amount of the asset, in its smallest unit.
+        pub amount: U256,
+    }
+
+    /// Outcome of one asset of a [`MerkleAirdrop::claim_multi_asset`] claim,
+    /// returned by [`MerkleAirdrop::asset_receipt`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct AssetPayout {
+        /// Asset Hub asset id of the entry.
+        pub id: AssetId,
+        /// Amount owed to the recipient, net of the claim fee and any late
+        /// penalty.
+        pub amount: U256,
+        /// Whether the amount reached the recipient. The campaign's own asset
+        /// is always settled, possibly through [`MerkleAirdrop::withdraw`] or
+        /// [`MerkleAirdrop::retry_claim`].
+        pub paid: bool,
+    }
 
-    /// Compute `keccak256(left || right)`.
-    fn hash(left: &[u8], right: &[u8]) -> [u8; 32] {
+    /// Step-by-step recomputation of a proof, returned by
+    /// [`MerkleAirdrop::trace_proof`] in builds with the `trace` feature.
+    #[cfg(feature = "trace")]
+    #[derive(Debug, Clone, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ProofTrace {
+        /// Leaf hash computed for the claim.
+        pub leaf: [u8; 32],
+        /// Node computed from each proof element, in proof order.
+        pub steps: Vec<[u8; 32]>,
+        /// Root the proof is checked against.
+        pub root: [u8; 32],
+        /// Whether the proof verifies, i.e. the last node is `root` and every
+        /// bit of the index was used.
+        pub verified: bool,
+    }
+
+    /// Settings of a campaign, passed to the [`MerkleAirdrop`] constructors.
+    ///
+    /// [`Default`] gives the most permissive settings but no tree and no
+    /// claim window, so `root`, `num_leaves` and `campaign_end_time` always
+    /// have to be set.
+    #[derive(Debug, Clone, PartialEq, Eq, ink::SolDecode, ink::SolEncode)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct CampaignConfig {
+        /// Merkle root of the distribution tree.
+        pub root: [u8; 32],
+        /// Block timestamp when claiming opens.
+        pub campaign_start_time: u64,
+        /// Block timestamp when claiming stops.
+        pub campaign_end_time: u64,
+        /// Hashing algorithm the Merkle tree was built with.
+        pub hash_algo: HashAlgo,
+        /// Accept token calls that succeed without returning a value, for
+        /// tokens whose `transfer` returns nothing; `false` is still a refusal.
+        pub lenient_transfers: bool,
+        /// Time after `campaign_end_time` before the owner may sweep, giving
+        /// stragglers a guaranteed grace period.
+        pub sweep_delay: u64,
+        /// Number of leaves in the tree, so auditors can check the published
+        /// allocation list against a committed count.
+        pub num_leaves: u64,
+        /// Off-chain pointer (IPFS CID / URL) with the campaign's name and
+        /// terms.
+        pub metadata_uri: String,
+        /// Reject [`MerkleAirdrop::fund`] once claiming has opened, so early
+        /// claimers never race a late deposit.
+        pub require_funding_before_start: bool,
+        /// Token amount per allocation unit, for trees whose leaves commit to
+        /// unit counts and are claimed via [`MerkleAirdrop::claim_units`].
+        pub unit_value: U256,
+        /// Multiplier from the amounts committed in the tree to the asset's
+        /// smallest unit, so one tree can serve assets with different decimals.
+        pub decimals_factor: U256,
+        /// Send swept residuals to [`BURN_ADDRESS`] instead of the owner,
+        /// making the post-campaign disposition trustless.
+        pub sweep_to_burn: bool,
+        /// Share of `expected_total`, in basis points, that must be claimed for
+        /// the first sweep to go ahead; below it the claim window is extended
+        /// once instead. `0` disables the extension.
+        pub auto_extend_threshold_bps: u16,
+        /// How long an automatic extension keeps claims open, measured from the
+        /// sweep attempt that triggered it.
+        pub auto_extend_duration: u64,
+        /// Total amount the tree allocates, against which
+        /// `auto_extend_threshold_bps` and `fund_deadline` are measured.
+        pub expected_total: U256,
+        /// Most the contract will ever pay out in claims, a backstop against a
+        /// tree that over-allocates.
+        pub max_distribution: U256,
+        /// Roots of additional trees sharding a very large allocation, each
+        /// with its number of leaves, claimed through
+        /// [`MerkleAirdrop::claim_from_tree`] by position.
+        pub shard_roots: Vec<([u8; 32], u64)>,
+        /// Credit claims to a pending balance paid out by
+        /// [`MerkleAirdrop::withdraw`] instead of transferring during the
+        /// claim, keeping proof verification free of external calls.
+        pub pull_payments: bool,
+        /// Most entries a single [`MerkleAirdrop::claim_batch`] call may hold,
+        /// so relayers never submit a batch too large to fit in a block.
+        pub max_batch: u32,
+        /// Only pay claims whose recipient the owner has registered with
+        /// [`MerkleAirdrop::set_allowed`], on top of a valid proof.
+        pub require_allowlist: bool,
+        /// Accept deposits after `campaign_end_time`, e.g. to pre-position
+        /// funds for a sweep; rejected by default.
+        pub allow_funding_after_end: bool,
+        /// Minimum milliseconds between two payouts to the same recipient, e.g.
+        /// across nonce entries; `0` disables it.
+        pub claim_cooldown: u64,
+        /// Contract to read the Merkle root from instead of `root`, e.g. a
+        /// shared registry; the zero address keeps the local root.
+        pub root_provider: Address,
+        /// Largest referral bonus [`MerkleAirdrop::claim_with_referrer`] may
+        /// pay, in basis points of the claim; `0` disables referrals.
+        pub max_referrer_bps: u16,
+        /// Share of each payout withheld as a claim fee, in basis points; fees
+        /// stay in the contract and are swept with unclaimed tokens.
+        pub fee_bps: u16,
+        /// Ceiling [`MerkleAirdrop::set_fee_bps`] can never raise `fee_bps`
+        /// above.
+        pub max_fee_bps: u16,
+        /// How long after `campaign_end_time` late claims are still accepted,
+        /// in milliseconds, at a `late_penalty_bps` cut.
+        pub grace_period: u64,
+        /// Share of each payout withheld during the grace period, in basis
+        /// points; swept with the unclaimed balance.
+        pub late_penalty_bps: u16,
+        /// Whether [`Claimed`] reports the contract's balance after each claim,
+        /// at the cost of an extra `balanceOf` call.
+        pub emit_remaining_balance: bool,
+        /// Delay between [`MerkleAirdrop::queue_end_time_change`] and the
+        /// earliest [`MerkleAirdrop::apply_end_time_change`], in milliseconds.
+        pub end_time_timelock: u64,
+        /// Root of an empty subtree at each level, from the empty leaf up, for
+        /// sparse trees whose proofs use [`ZERO_SIBLING`]; empty to disable.
+        pub zero_hashes: Vec<[u8; 32]>,
+        /// Time by which the campaign must be funded with at least
+        /// `expected_total`, no later than `campaign_start_time`; past it
+        /// funding closes and an underfunded campaign is cancelled. `0`
+        /// disables it.
+        pub fund_deadline: u64,
+    }
+
+    impl Default for CampaignConfig {
+        fn default() -> Self {
+            Self {
+                root: [0u8; 32],
+                campaign_start_time: 0,
+                campaign_end_time: 0,
+                hash_algo: HashAlgo::Keccak256,
+                lenient_transfers: false,
+                sweep_delay: 0,
+                num_leaves: 0,
+                metadata_uri: String::new(),
+                require_funding_before_start: false,
+                unit_value: U256::from(1),
+                decimals_factor: U256::from(1),
+                sweep_to_burn: false,
+                auto_extend_threshold_bps: 0,
+                auto_extend_duration: 0,
+                expected_total: U256::zero(),
+                max_distribution: U256::MAX,
+                shard_roots: Vec::new(),
+                pull_payments: false,
+                max_batch: 16,
+                require_allowlist: false,
+                allow_funding_after_end: false,
+                claim_cooldown: 0,
+                root_provider: Address::zero(),
+                max_referrer_bps: 0,
+                fee_bps: 0,
+                max_fee_bps: 0,
+                grace_period: 0,
+                late_penalty_bps: 0,
+                emit_remaining_balance: false,
+                end_time_timelock: 0,
+                zero_hashes: Vec::new(),
+                fund_deadline: 0,
+            }
+        }
+    }
+
+    /// Hash `input` with `H` into a 256-bit buffer.
+    fn hash_with<H>(input: &[u8]) -> [u8; 32]
+    where
+        H: CryptoHash + HashOutput<Type = [u8; 32]>,
+    {
+        let mut output = <H as HashOutput>::Type::default(); // 256-bit buffer
+        hash_bytes::<H>(input, &mut output);
+
+        output
+    }
+
+    /// Compute `H(left || right)` using the given algorithm.
+    fn hash(algo: HashAlgo, left: &[u8], right: &[u8]) -> [u8; 32] {
         let mut input = Vec::with_capacity(left.len() + right.len());
         input.extend_from_slice(left);
         input.extend_from_slice(right);
-        let mut output = <Keccak256 as HashOutput>::Type::default(); // 256-bit buffer
-        hash_bytes::<Keccak256>(&input, &mut output);
 
-        output
+        match algo {
+            HashAlgo::Keccak256 => hash_with::<Keccak256>(&input),
+            HashAlgo::Blake2x256 => hash_with::<Blake2x256>(&input),
+            HashAlgo::Sha2x256 => hash_with::<Sha2x256>(&input),
+        }
     }
 
     /// Verify that a leaf is part of a Merkle tree with the given root.
-    fn verify_proof<'a>(leaf: [u8; 32], proof: &'a [[u8; 32]], index: u64, root: [u8; 32]) -> bool {
+    fn verify_proof<'a>(
+        algo: HashAlgo,
+        leaf: [u8; 32],
+        proof: &'a [[u8; 32]],
+        index: u64,
+        root: [u8; 32],
+    ) -> bool {
         let mut computed = leaf;
         let mut index = index;
 
+        #[cfg(all(feature = "trace", feature = "std"))]
+        eprintln!("verify_proof: leaf {leaf:?} at index {index}");
+
         for sibling in proof.iter() {
             if index % 2 == 0 {
-                computed = hash(&computed, sibling); // current node is left child
+                computed = hash(algo, &computed, sibling); // current node is left child
             } else {
-                computed = hash(sibling, &computed); // current node is right child
+                computed = hash(algo, sibling, &computed); // current node is right child
             }
             index /= 2;
+
+            #[cfg(all(feature = "trace", feature = "std"))]
+            eprintln!("verify_proof: sibling {sibling:?} -> {computed:?}");
+        }
+
+        #[cfg(all(feature = "trace", feature = "std"))]
+        eprintln!("verify_proof: computed {computed:?}, root {root:?}, index left {index}");
+
+        // Every bit of `index` must be consumed, or a proof shorter than the
+        // leaf's depth would be checked against an inner node's position.
+        index == 0 && computed == root
+    }
+
+    /// Verify that several leaves are part of a Merkle tree with the given
+    /// root, sharing one `proof`.
+    ///
+    /// A positional take on OpenZeppelin's multiproofs. `leaves` are
+    /// `(index, leaf)` pairs in ascending index order. Nodes are consumed
+    /// from a queue of the leaves followed by each computed parent; every
+    /// entry of `proof_flags` hashes the next queued node with either the
+    /// node after it (`true`, which must be its sibling) or the next
+    /// `proof` element (`false`). Each node's index decides whether it is
+    /// the left or the right child, as in [`verify_proof`].
+    fn verify_multiproof(
+        algo: HashAlgo,
+        leaves: &[(u64, [u8; 32])],
+        proof: &[[u8; 32]],
+        proof_flags: &[bool],
+        root: [u8; 32],
+    ) -> bool {
+        if leaves.is_empty() || leaves.len() + proof.len() != proof_flags.len() + 1 {
+            return false;
+        }
+
+        // (level, index, hash) of every node, queued in processing order.
+        let mut queue: Vec<(u32, u64, [u8; 32])> =
+            Vec::with_capacity(leaves.len() + proof_flags.len());
+        queue.extend(leaves.iter().map(|&(index, leaf)| (0, index, leaf)));
+        let mut next = 0;
+        let mut proof_pos = 0;
+
+        for &flag in proof_flags {
+            let Some(&(level, index, node)) = queue.get(next) else {
+                return false;
+            };
+            next += 1;
+
+            let sibling = if flag {
+                match queue.get(next) {
+                    Some(&(sibling_level, sibling_index, sibling))
+                        if sibling_level == level && sibling_index == index ^ 1 =>
+                    {
+                        next += 1;
+                        sibling
+                    }
+                    _ => return false,
+                }
+            } else {
+                let Some(&sibling) = proof.get(proof_pos) else {
+                    return false;
+                };
+                proof_pos += 1;
+                sibling
+            };
+
+            let parent = if index % 2 == 0 {
+                hash(algo, &node, &sibling)
+            } else {
+                hash(algo, &sibling, &node)
+            };
+            queue.push((level + 1, index / 2, parent));
+        }
+
+        // Everything must be consumed down to a single node at position 0,
+        // for the same reason `verify_proof` consumes every index bit.
+        matches!(
+            queue.last(),
+            Some(&(_, 0, computed)) if computed == root
+        ) && next + 1 == queue.len()
+            && proof_pos == proof.len()
+    }
+
+    /// Compute the parent of `a` and `b` in a sorted-pair tree.
+    ///
+    /// The smaller hash, compared as big-endian bytes, goes first, so proofs
+    /// need no index. Equal siblings, e.g. two identical leaves, hash as
+    /// `a || b`; either order gives the same parent, so ties stay unambiguous.
+    fn hash_sorted_pair(algo: HashAlgo, a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        if a <= b {
+            hash(algo, a, b)
+        } else {
+            hash(algo, b, a)
         }
+    }
+
+    /// Verify that a leaf is part of a sorted-pair Merkle tree with the given root.
+    fn verify_proof_sorted(
+        algo: HashAlgo,
+        leaf: [u8; 32],
+        proof: &[[u8; 32]],
+        root: [u8; 32],
+    ) -> bool {
+        let computed = proof.iter().fold(leaf, |computed, sibling| {
+            hash_sorted_pair(algo, &computed, sibling)
+        });
 
         computed == root
     }
 
+    /// `ceil(log2(leaf_count))`: the levels above the leaves of a tree of
+    /// `leaf_count` leaves, with zero leaves treated like one.
+    fn depth_for(leaf_count: u64) -> u32 {
+        u64::BITS - leaf_count.saturating_sub(1).leading_zeros()
+    }
+
+    /// Split a concatenated proof into its 32-byte siblings.
+    ///
+    /// Returns `None` if `bytes` is not a whole number of hashes.
+    fn unpack_proof(bytes: &[u8]) -> Option<Vec<[u8; 32]>> {
+        if bytes.len() % 32 != 0 {
+            return None;
+        }
+
+        Some(
+            bytes
+                .chunks_exact(32)
+                .map(|chunk| {
+                    let mut sibling = [0u8; 32];
+                    sibling.copy_from_slice(chunk);
+                    sibling
+                })
+                .collect(),
+        )
+    }
+
     /// Event emitted when a recipient successfully claims their airdrop.
     #[ink(event)]
     pub struct Claimed {
         /// The address of the recipient.
         #[ink(topic)]
         recipient: Address,
-        /// Amount of tokens claimed.
+        /// Amount of tokens paid out, net of the claim fee and any late
+        /// penalty.
         value: U256,
+        /// The contract's token balance right after the claim, or zero unless
+        /// deployed with `emit_remaining_balance`.
+        remaining_after: U256,
+    }
+
+    /// Event emitted when tokens are deposited into the campaign.
+    #[ink(event)]
+    pub struct Funded {
+        /// The address that supplied the tokens.
+        #[ink(topic)]
+        funder: Address,
+        /// Amount of tokens deposited.
+        amount: U256,
+    }
+
+    /// Event emitted when a funder reclaims their share of unclaimed tokens.
+    #[ink(event)]
+    pub struct Refunded {
+        /// The funder receiving the refund.
+        funder: Address,
+        /// Amount of tokens refunded.
+        amount: U256,
+    }
+
+    /// Event emitted when the owner reverses a recent claim.
+    #[ink(event)]
+    pub struct ClaimReversed {
+        /// The address whose claim was reversed.
+        #[ink(topic)]
+        recipient: Address,
+    }
+
+    /// Event emitted when the owner sweeps the remaining balance.
+    #[ink(event)]
+    pub struct Swept {
+        /// The address receiving the swept tokens.
+        #[ink(topic)]
+        to: Address,
+        /// Amount of tokens swept.
+        amount: U256,
+    }
+
+    /// Event emitted when the owner replaces the asset contract.
+    #[ink(event)]
+    pub struct AssetContractUpdated {
+        /// Address of the new asset contract.
+        asset_contract: Address,
+    }
+
+    /// Event emitted when the campaign metadata URI changes.
+    #[ink(event)]
+    pub struct MetadataUpdated {
+        /// The new metadata URI.
+        uri: String,
+    }
+
+    /// Event emitted when a sweep extends an under-claimed campaign instead.
+    #[ink(event)]
+    pub struct CampaignAutoExtended {
+        /// The new `campaign_end_time`.
+        new_end_time: u64,
+    }
+
+    /// Event emitted when ownership changes, including the initial
+    /// assignment at construction (from the zero address).
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        /// The previous owner.
+        #[ink(topic)]
+        previous: Address,
+        /// The new owner.
+        #[ink(topic)]
+        new: Address,
+    }
+
+    /// Event emitted when a claimant withdraws a pending balance.
+    #[ink(event)]
+    pub struct Withdrawn {
+        /// The claimant receiving the tokens.
+        #[ink(topic)]
+        recipient: Address,
+        /// Amount of tokens withdrawn.
+        amount: U256,
+    }
+
+    /// Event emitted when the owner moves the campaign end earlier.
+    #[ink(event)]
+    pub struct CampaignShortened {
+        /// The new `campaign_end_time`.
+        new_end_time: u64,
+    }
+
+    /// Event emitted when the owner changes a recipient's allowlist status.
+    #[ink(event)]
+    pub struct AllowlistUpdated {
+        /// The recipient whose status changed.
+        #[ink(topic)]
+        who: Address,
+        /// Whether the recipient may now claim.
+        allowed: bool,
+    }
+
+    /// Event emitted when the owner marks addresses claimed for a migration.
+    #[ink(event)]
+    pub struct ClaimedSeeded {
+        /// Number of addresses in the batch.
+        count: u32,
+    }
+
+    /// Event emitted when the Merkle root is replaced, by the owner or a new
+    /// root read from `root_provider`.
+    #[ink(event)]
+    pub struct RootUpdated {
+        /// The root that was replaced.
+        previous_root: [u8; 32],
+        /// The new root.
+        new_root: [u8; 32],
+        /// Sequence number of the new root, for [`MerkleAirdrop::claim_against_root`].
+        root_index: u32,
+    }
+
+    /// Event emitted when the owner appoints or removes the operator.
+    #[ink(event)]
+    pub struct OperatorUpdated {
+        /// The previous operator.
+        #[ink(topic)]
+        previous: Address,
+        /// The new operator, or the zero address if removed.
+        #[ink(topic)]
+        new: Address,
+    }
+
+    /// Event emitted when claims are paused or resumed.
+    #[ink(event)]
+    pub struct PauseUpdated {
+        /// Whether claims are now paused.
+        paused: bool,
+    }
+
+    /// Event emitted for each asset paid by a multi-asset claim.
+    #[ink(event)]
+    pub struct AssetClaimed {
+        /// The address of the recipient.
+        #[ink(topic)]
+        recipient: Address,
+        /// Asset Hub asset id that was paid.
+        id: AssetId,
+        /// Amount of the asset paid.
+        amount: U256,
+    }
+
+    /// Event emitted when the owner redirects or restores a recipient's payouts.
+    #[ink(event)]
+    pub struct PayoutOverrideUpdated {
+        /// The recipient whose payouts are redirected.
+        #[ink(topic)]
+        who: Address,
+        /// Where their payouts now go, or the zero address if restored.
+        to: Address,
+    }
+
+    /// Event emitted when a claim pays a referral bonus.
+    #[ink(event)]
+    pub struct ReferralPaid {
+        /// The referrer receiving the bonus.
+        #[ink(topic)]
+        referrer: Address,
+        /// The recipient whose claim named the referrer.
+        recipient: Address,
+        /// Amount of tokens paid to the referrer.
+        amount: U256,
+    }
+
+    /// Event emitted when the owner changes the claim fee.
+    #[ink(event)]
+    pub struct FeeUpdated {
+        /// The previous `fee_bps`.
+        old: u16,
+        /// The new `fee_bps`.
+        new: u16,
+    }
+
+    /// Event emitted when a claim is locked in escrow.
+    #[ink(event)]
+    pub struct Escrowed {
+        /// The claimant the escrow belongs to.
+        #[ink(topic)]
+        recipient: Address,
+        /// Amount of tokens locked.
+        amount: U256,
+        /// When [`MerkleAirdrop::withdraw_escrow`] unlocks, in milliseconds.
+        unlock_time: u64,
+    }
+
+    /// Event emitted when the owner enables or disables claims.
+    #[ink(event)]
+    pub struct ClaimsEnabledUpdated {
+        /// Whether claims are now enabled.
+        enabled: bool,
+    }
+
+    /// Event emitted when funding is closed.
+    #[ink(event)]
+    pub struct FundingFinalized {
+        /// Whether the campaign was funded enough to go ahead; if not, it has
+        /// been cancelled.
+        success: bool,
+        /// The final `total_funded` that shares are paid from.
+        total_funded: U256,
+    }
+
+    /// Event emitted when the owner queues a new campaign end time.
+    #[ink(event)]
+    pub struct EndTimeChangeQueued {
+        /// The queued `campaign_end_time`.
+        new_end_time: u64,
+        /// When the change may be applied.
+        effective_at: u64,
+    }
+
+    /// Event emitted when a queued campaign end time takes effect.
+    #[ink(event)]
+    pub struct EndTimeChanged {
+        /// The new `campaign_end_time`.
+        new_end_time: u64,
+    }
+
+    /// Event emitted when the owner corrects `decimals_factor`.
+    #[ink(event)]
+    pub struct DecimalsFactorUpdated {
+        /// The previous `decimals_factor`.
+        old: U256,
+        /// The new `decimals_factor`.
+        new: U256,
+    }
+
+    /// Event emitted when a verified claim's transfer fails and the payout
+    /// is kept for [`MerkleAirdrop::retry_claim`].
+    #[ink(event)]
+    pub struct PaymentDeferred {
+        /// The claimant the payout is owed to.
+        #[ink(topic)]
+        recipient: Address,
+        /// Amount of tokens owed.
+        amount: U256,
     }
 
     /// Errors that can occur when funding, claiming, or sweeping.
@@ -91,11 +897,173 @@ mod merke_airdrop {
         ClaimPeriodOver,
         /// Claim period is still active (sweep not yet allowed).
         ClaimPeriodActive,
+        /// Caller is not the registered delegate of the recipient.
+        NotDelegate,
+        /// Recipient has not claimed, so there is nothing to reverse.
+        NotClaimed,
+        /// The claim is older than [`CLAIM_REVERSAL_WINDOW`].
+        ReversalWindowClosed,
+        /// Caller has no outstanding contribution to refund.
+        NothingToRefund,
+        /// Arithmetic overflow while computing token amounts.
+        Overflow,
+        /// Claiming has not started yet.
+        ClaimPeriodNotStarted,
+        /// Funding is only accepted before the campaign starts.
+        FundingClosed,
+        /// The campaign has already received funds.
+        AlreadyFunded,
+        /// Unclaimed tokens must be swept to [`BURN_ADDRESS`].
+        BurnRequired,
+        /// Leaf index is not below the committed `num_leaves`.
+        IndexOutOfRange,
+        /// Paying the claim would exceed `max_distribution`.
+        DistributionCapExceeded,
+        /// The contract holds no tokens to sweep.
+        NothingToSweep,
+        /// No shard tree exists with the given id.
+        UnknownTree,
+        /// Caller has no pending withdrawal.
+        NothingToWithdraw,
+        /// Batch holds more than `max_batch` entries.
+        BatchTooLarge,
+        /// The signed claim authorization is past its deadline.
+        SignatureExpired,
+        /// The signed claim authorization has already been used.
+        SignatureAlreadyUsed,
+        /// The signature does not recover to the recipient.
+        InvalidSignature,
+        /// The requested end time is not allowed.
+        InvalidEndTime,
+        /// Recipient is not on the allowlist.
+        NotAllowed,
+        /// Returned if the asset rejects the permit, or does not support one.
+        PermitFailed,
+        /// Returned if claims are seeded after the first claim was paid.
+        ClaimsAlreadyStarted,
+        /// Returned if a recipient claims again before `claim_cooldown` has passed.
+        Cooldown,
+        /// Returned if a historical root was never set, was overwritten, or is past its grace period.
+        RootExpired,
+        /// Returned if claims are paused.
+        Paused,
+        /// Returned if `init` is called on a configured campaign.
+        AlreadyInitialized,
+        /// Returned if `init` is given a zero root or an empty tree.
+        InvalidRoot,
+        /// Returned if the zero address is given where an account is required.
+        ZeroAddress,
+        /// Returned if reading the root from `root_provider` fails.
+        RootProviderCallFailed,
+        /// Returned if a claimant names themselves as referrer.
+        SelfReferral,
+        /// Returned if a referral bonus exceeds `max_referrer_bps`.
+        ReferralTooLarge,
+        /// Returned if a new fee exceeds `max_fee_bps`.
+        FeeTooHigh,
+        /// Returned if an escrow is withdrawn before its unlock time.
+        EscrowLocked,
+        /// Returned if the owner has disabled claims.
+        ClaimsDisabled,
+        /// Returned if a share claim is made before funding is finalized.
+        FundingNotFinalized,
+        /// Returned if a multiproof does not verify against the root.
+        InvalidMultiproof,
+        /// Returned if a queued change is applied before its timelock has passed.
+        TimelockNotElapsed,
+        /// Returned if a transfer is attempted while another one is in flight.
+        Reentrant,
+        /// Returned if `retry_claim` is called by an account owed nothing.
+        NothingOwed,
+        /// Returned if the campaign has been cancelled, e.g. for missing its `fund_deadline`.
+        CampaignCancelled,
+        /// Returned if a claim is reversed while its payout is still pending, owed or escrowed.
+        PayoutOutstanding,
+        /// Returned if the root is replaced while the campaign reads it from a `root_provider`.
+        RootProviderActive,
+        /// Returned if a claim names the zero address as its referrer.
+        ZeroReferrer,
+        /// Returned if the contract's unreserved balance cannot cover a referral bonus.
+        InsufficientBalance,
+        /// Returned if a `new_uninitialized` campaign is used before `init` configures it.
+        NotInitialized,
     }
 
+    /// How long after a claim the owner may still reverse it, in milliseconds.
+    pub const CLAIM_REVERSAL_WINDOW: u64 = 60 * 60 * 1000;
+
+    /// Destination of swept tokens when `sweep_to_burn` is set
+    /// (`0x000000000000000000000000000000000000dEaD`).
+    pub const BURN_ADDRESS: Address = H160([
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0xde, 0xad,
+    ]);
+
+    /// Basis points representing 100%.
+    pub const MAX_BPS: u16 = 10_000;
+
+    /// Longest allowed claim window from deployment, in milliseconds (five
+    /// years), to catch end times mistyped by orders of magnitude.
+    pub const MAX_DURATION: u64 = 5 * 365 * 24 * 60 * 60 * 1000;
+
+    /// Number of replaced roots kept for [`MerkleAirdrop::claim_against_root`].
+    pub const ROOT_HISTORY_SIZE: u32 = 8;
+
+    /// Number of recent claim timestamps kept for
+    /// [`MerkleAirdrop::claims_in_range`].
+    pub const CLAIM_LOG_SIZE: u64 = 4096;
+
+    /// Proof element standing for the empty subtree at its level, when the
+    /// campaign is deployed with `zero_hashes`. Sparse trees have mostly
+    /// empty siblings, and zero bytes are the cheapest calldata.
+    pub const ZERO_SIBLING: [u8; 32] = [0u8; 32];
+
+    /// Leaf type tag of a `(recipient, units)` leaf for
+    /// [`MerkleAirdrop::claim_units`], the first byte of its preimage.
+    ///
+    /// Every leaf kind added after plain `(recipient, value)` leaves has its
+    /// own tag, so a leaf built for one claim message never verifies as a
+    /// leaf of another, even where the rest of the two preimages have the
+    /// same length. Plain leaves keep their untagged
+    /// `H(recipient || value)` encoding, whose 52-byte preimage no tagged
+    /// kind shares.
+    pub const LEAF_UNITS: u8 = 0x01;
+
+    /// Leaf type tag of a `(recipient, share_bps)` leaf for
+    /// [`MerkleAirdrop::claim_share`].
+    pub const LEAF_SHARE: u8 = 0x02;
+
+    /// Leaf type tag of a `(recipient, nonce, value)` leaf for
+    /// [`MerkleAirdrop::claim_with_nonce`].
+    pub const LEAF_NONCE: u8 = 0x03;
+
+    /// Leaf type tag of a `(recipient, value, unlock_time)` leaf for
+    /// [`MerkleAirdrop::claim_to_escrow`].
+    pub const LEAF_ESCROW: u8 = 0x04;
+
+    /// Leaf type tag of a `(recipient, assets)` leaf for
+    /// [`MerkleAirdrop::claim_multi_asset`].
+    pub const LEAF_MULTI_ASSET: u8 = 0x05;
+
+    /// Number of entries kept in [`MerkleAirdrop::root_history`].
+    pub const ROOT_LOG_SIZE: u32 = 32;
+
+    /// How long a replaced root still accepts proofs, in milliseconds.
+    pub const ROOT_GRACE_PERIOD: u64 = 15 * 60 * 1000;
+
+    /// Domain tag at the start of every [`MerkleAirdrop::claim_with_sig`]
+    /// digest, so a claim signature cannot be passed off as a signature over
+    /// some other message of the same length.
+    pub const CLAIM_SIG_DOMAIN: &[u8] = b"MerkleAirdrop.claim_with_sig";
+
     /// Standard `Result` type for contract operations.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// Outcome of a `transfer` or `transferFrom` call on the asset contract
+    /// made with `try_invoke`, so undecodable output is not a trap.
+    type TokenCall =
+        ink::env::Result<ink::MessageResult<core::result::Result<bool, assets::Error>>>;
+
     /// Merkle-based ERC20 token airdrop contract.
     #[ink(storage)]
     pub struct MerkleAirdrop {
@@ -103,12 +1071,182 @@ mod merke_airdrop {
         pub asset_contract: AssetHubPrecompileRef,
         /// Merkle root committing to `(address, amount)` pairs.
         pub root: [u8; 32],
+        /// Number of leaves in the tree committed to by `root`.
+        pub num_leaves: u64,
         /// Tracks whether an address has already claimed.
         pub claimed: Mapping<Address, bool>,
+        /// Bitmap of claimed leaf indices, keyed by `index / 256`.
+        pub claimed_bitmap: Mapping<u64, U256>,
+        /// Delegate each recipient authorized to claim on their behalf.
+        pub delegates: Mapping<Address, Address>,
+        /// Block timestamp at which each address claimed.
+        pub claimed_at: Mapping<Address, u64>,
+        /// Leaf index each address claimed from.
+        pub claimed_index: Mapping<Address, u64>,
         /// Owner authorized for administrative functions.
         pub owner: Address,
+        /// Block timestamp before which claims are rejected.
+        pub campaign_start_time: u64,
         /// Block timestamp after which claims are rejected.
         pub campaign_end_time: u64,
+        /// Hashing algorithm used for leaves and nodes.
+        pub hash_algo: HashAlgo,
+        /// Whether token calls that succeed without returning a `bool` count
+        /// as successful once the contract's balance shows the tokens moved.
+        pub lenient_transfers: bool,
+        /// Delay after `campaign_end_time` before the owner may sweep.
+        pub sweep_delay: u64,
+        /// Off-chain pointer describing the campaign (IPFS CID / URL).
+        pub metadata_uri: String,
+        /// Cumulative amount deposited by each funder.
+        pub contributions: Mapping<Address, U256>,
+        /// Sum of all outstanding entries in `contributions`.
+        pub total_contributions: U256,
+        /// Whether `fund` is rejected once `campaign_start_time` is reached.
+        pub require_funding_before_start: bool,
+        /// Token amount paid per unit by [`claim_units`].
+        pub unit_value: U256,
+        /// Sum of all amounts paid out by claims.
+        pub total_claimed: U256,
+        /// Tracks which `(address, nonce)` entries have been claimed.
+        pub claimed_nonces: Mapping<(Address, u64), bool>,
+        /// Whether any deposit has ever been made.
+        pub funded: bool,
+        /// Multiplier applied to committed amounts before paying them out.
+        pub decimals_factor: U256,
+        /// Whether sweeps send the residual balance to [`BURN_ADDRESS`].
+        pub sweep_to_burn: bool,
+        /// Cumulative amount each address has been paid by claims.
+        pub claimed_amounts: Mapping<Address, U256>,
+        /// Claimed share of `expected_total`, in basis points, below which the
+        /// first sweep extends the campaign instead. `0` disables it.
+        pub auto_extend_threshold_bps: u16,
+        /// How long an automatic extension reopens claims for.
+        pub auto_extend_duration: u64,
+        /// Total amount the tree allocates, for the auto-extension threshold
+        /// and the funding deadline.
+        pub expected_total: U256,
+        /// Whether the campaign has already been extended automatically.
+        pub auto_extended: bool,
+        /// Upper bound on `total_claimed`.
+        pub max_distribution: U256,
+        /// Roots of additional trees claimed through [`claim_from_tree`], each
+        /// with the number of leaves it commits to.
+        pub shard_roots: Vec<([u8; 32], u64)>,
+        /// Tracks which addresses have claimed from each shard tree.
+        pub tree_claimed: Mapping<(u32, Address), bool>,
+        /// Whether claims credit `pending_withdrawals` instead of transferring.
+        pub pull_payments: bool,
+        /// Claimed amounts awaiting [`withdraw`], when `pull_payments` is set.
+        pub pending_withdrawals: Mapping<Address, U256>,
+        /// Sum of all `pending_withdrawals`, `escrows` and `owed`, excluded
+        /// from sweeps and refunds.
+        pub reserved: U256,
+        /// Most entries accepted by a single [`claim_batch`] call.
+        pub max_batch: u32,
+        /// Signed claim digests already consumed by [`claim_with_sig`].
+        pub used_signatures: Mapping<[u8; 32], bool>,
+        /// Whether claims also require the recipient to be on `allowlist`.
+        pub require_allowlist: bool,
+        /// Recipients the owner registered as allowed to claim.
+        pub allowlist: Mapping<Address, bool>,
+        /// `keccak256` of the concatenated proof each address claimed with.
+        pub proof_digests: Mapping<Address, [u8; 32]>,
+        /// Number of leaves in the main tree claimed so far.
+        pub claimed_count: u64,
+        /// Whether `fund` still accepts deposits once the campaign has ended.
+        pub allow_funding_after_end: bool,
+        /// Minimum time between two payouts to the same recipient, in milliseconds.
+        pub claim_cooldown: u64,
+        /// Block timestamp of the latest payout to each recipient.
+        pub last_payout_at: Mapping<Address, u64>,
+        /// Sequence number of `root`, incremented by every `update_root` and
+        /// by every new root read from `root_provider`.
+        pub root_index: u32,
+        /// Replaced roots and when they were replaced, in slot
+        /// `root_index % ROOT_HISTORY_SIZE`.
+        pub recent_roots: Mapping<u32, ([u8; 32], u64)>,
+        /// Hot key allowed to pause claims and update the root, but not
+        /// to move funds. The zero address until the owner sets one.
+        pub operator: Address,
+        /// Whether claim payouts are suspended.
+        pub paused: bool,
+        /// Receipt of each address's claim from the main tree.
+        pub receipts: Mapping<Address, ClaimReceipt>,
+        /// Address each recipient's claims are paid to instead of the recipient.
+        pub payout_overrides: Mapping<Address, Address>,
+        /// Whether the campaign is configured; only `false` between
+        /// `new_uninitialized` and `init`.
+        pub initialized: bool,
+        /// Committed amount of each `(recipient, nonce)` entry already paid
+        /// by `claim_all_vested`.
+        pub vested_claimed: Mapping<(Address, u64), U256>,
+        /// Contract the root is read from, or the zero address to use `root`.
+        pub root_provider: Address,
+        /// Block in which `root` was last read from `root_provider`.
+        pub root_synced_at: Option<u32>,
+        /// Sum of every deposit ever made, unaffected by claims and refunds.
+        pub total_funded: U256,
+        /// Largest referral bonus `claim_with_referrer` may pay, in basis points.
+        pub max_referrer_bps: u16,
+        /// Share of each payout withheld as a claim fee, in basis points.
+        pub fee_bps: u16,
+        /// Ceiling on `fee_bps`, fixed at deployment to protect recipients.
+        pub max_fee_bps: u16,
+        /// Claimed amounts locked by [`claim_to_escrow`], with their unlock times.
+        pub escrows: Mapping<Address, (U256, u64)>,
+        /// Whether the owner has opened claims; unlike `paused`, a deliberate
+        /// administrative state rather than an emergency stop.
+        pub claims_enabled: bool,
+        /// The last [`ROOT_LOG_SIZE`] roots the campaign has used with when
+        /// they were set, keyed by their `root_index % ROOT_LOG_SIZE`; the
+        /// deployment or [`init`] root is number 0.
+        pub root_log: Mapping<u32, ([u8; 32], u64)>,
+        /// Whether the owner has closed funding, fixing `total_funded` for
+        /// [`claim_share`].
+        pub funding_finalized: bool,
+        /// Whether the campaign has been cancelled.
+        pub cancelled: bool,
+        /// How long after `campaign_end_time` late claims are still accepted.
+        pub grace_period: u64,
+        /// Share of each payout withheld during the grace period, in basis points.
+        pub late_penalty_bps: u16,
+        /// Whether [`Claimed`] reports `remaining_after`, costing a `balanceOf` call.
+        pub emit_remaining_balance: bool,
+        /// Timestamps of the last [`CLAIM_LOG_SIZE`] claims, stored at
+        /// `claim_log_len % CLAIM_LOG_SIZE`.
+        pub claim_times: Mapping<u64, u64>,
+        /// Number of claims ever written to `claim_times`.
+        pub claim_log_len: u64,
+        /// Delay before a queued `campaign_end_time` change can be applied.
+        pub end_time_timelock: u64,
+        /// End time queued by [`queue_end_time_change`], or zero if none.
+        pub pending_end_time: u64,
+        /// When `pending_end_time` may be applied.
+        pub end_time_effective_at: u64,
+        /// Root of an empty subtree at each level, substituted for [`ZERO_SIBLING`]
+        /// proof elements.
+        pub zero_hashes: Vec<[u8; 32]>,
+        /// Set while [`transfer_out`] is calling the asset contract. Kept in its
+        /// own storage cell, since plain fields are only written back once the
+        /// outermost message returns and re-entrant calls would not see them.
+        pub transfer_lock: Lazy<bool>,
+        /// Verified claim payouts whose transfer failed, awaiting [`retry_claim`].
+        pub owed: Mapping<Address, U256>,
+        /// Time by which `expected_total` must have been funded, or `0` for none.
+        pub fund_deadline: u64,
+        /// Receipt of each address's claim from each shard tree.
+        pub tree_receipts: Mapping<(u32, Address), ClaimReceipt>,
+        /// Per-asset outcome of each recipient's [`claim_multi_asset`], in leaf order.
+        pub asset_receipts: Mapping<Address, Vec<AssetPayout>>,
+        /// Every leaf index covered by each recipient's [`claim_multi`], so
+        /// [`reverse_claim`] can release them all.
+        pub multi_claim_indices: Mapping<Address, Vec<u64>>,
+        /// Refused [`claim_multi_asset`] payouts of other assets, per
+        /// recipient and asset, awaiting [`retry_asset_claim`].
+        pub owed_assets: Mapping<(Address, AssetId), U256>,
+        /// Sum of `owed_assets` per asset, never swept by [`sweep_all`].
+        pub reserved_assets: Mapping<AssetId, U256>,
     }
 
     impl MerkleAirdrop {
@@ -118,7 +1256,7 @@ mod merke_airdrop {
         /// - setting the ERC20 asset contract reference,
         /// - committing to the Merkle root,
         /// - configuring the claim window,
-        /// - recording the contract owner.
+        /// - recording the contract owner and emitting [`OwnershipTransferred`].
         ///
         /// **Note:** This constructor does not transfer in the campaign tokens.
         /// The caller must invoke [`fund`] immediately after deployment
@@ -126,36 +1264,271 @@ mod merke_airdrop {
         ///
         /// # Arguments
         /// - `asset_contract_address`: address of the asset contract code.
-        /// - `root`: Merkle root of the distribution tree.
-        /// - `campaign_end_time`: block timestamp when claiming stops.
+        /// - `config`: the campaign's settings, see [`CampaignConfig`].
         ///
         /// # Panics
+        /// In terms of the fields of `config`:
         /// - If the provided `campaign_end_time` is already in the past.
+        /// - If `campaign_end_time` is more than [`MAX_DURATION`] away.
+        /// - If `campaign_start_time` is not before `campaign_end_time`.
+        /// - If the provided `root` is all zero bytes, which usually means the
+        ///   off-chain tree generator never ran.
+        /// - If `num_leaves` is zero.
+        /// - If `decimals_factor` is zero.
+        /// - If `auto_extend_threshold_bps` exceeds [`MAX_BPS`].
+        /// - If `max_referrer_bps` exceeds [`MAX_BPS`].
+        /// - If `max_fee_bps` exceeds [`MAX_BPS`], or `fee_bps` exceeds `max_fee_bps`.
+        /// - If `late_penalty_bps` exceeds [`MAX_BPS`].
+        /// - If `fund_deadline` is after `campaign_start_time`.
+        /// - If an entry of `zero_hashes` is not the parent of two copies of
+        ///   the entry below it.
+        /// - If the deployer is the zero address, which would leave the admin
+        ///   functions permanently uncallable.
         #[ink(constructor, payable)]
-        pub fn new(
-            asset_contract_address: Address,
-            root: [u8; 32],
-            campaign_end_time: u64,
-        ) -> Self {
+        pub fn new(asset_contract_address: Address, config: CampaignConfig) -> Self {
+            let CampaignConfig {
+                root,
+                campaign_start_time,
+                campaign_end_time,
+                hash_algo,
+                lenient_transfers,
+                sweep_delay,
+                num_leaves,
+                metadata_uri,
+                require_funding_before_start,
+                unit_value,
+                decimals_factor,
+                sweep_to_burn,
+                auto_extend_threshold_bps,
+                auto_extend_duration,
+                expected_total,
+                max_distribution,
+                shard_roots,
+                pull_payments,
+                max_batch,
+                require_allowlist,
+                allow_funding_after_end,
+                claim_cooldown,
+                root_provider,
+                max_referrer_bps,
+                fee_bps,
+                max_fee_bps,
+                grace_period,
+                late_penalty_bps,
+                emit_remaining_balance,
+                end_time_timelock,
+                zero_hashes,
+                fund_deadline,
+            } = config;
+
             let now = Self::env().block_timestamp();
             // Fail if campaign already ended or ends immediately
             assert!(
                 campaign_end_time > now,
                 "Campaign end time must be in the future"
             );
+            assert!(
+                campaign_end_time - now <= MAX_DURATION,
+                "Campaign must not run longer than MAX_DURATION"
+            );
+            assert!(
+                campaign_start_time < campaign_end_time,
+                "Campaign start time must be before its end time"
+            );
+            // Fail on an unset root, which no proof can ever satisfy
+            assert!(root != [0u8; 32], "Merkle root must not be zero");
+            assert!(num_leaves > 0, "Merkle tree must have at least one leaf");
+            assert!(
+                shard_roots
+                    .iter()
+                    .all(|&(shard_root, shard_leaves)| shard_root != [0u8; 32] && shard_leaves > 0),
+                "Shard roots must not be zero and must have at least one leaf"
+            );
+            assert!(
+                !decimals_factor.is_zero(),
+                "Decimals factor must not be zero"
+            );
+            assert!(
+                auto_extend_threshold_bps <= MAX_BPS,
+                "Auto-extension threshold must not exceed 100%"
+            );
+            assert!(
+                max_referrer_bps <= MAX_BPS,
+                "Referral bonus cap must not exceed 100%"
+            );
+            assert!(max_fee_bps <= MAX_BPS, "Fee ceiling must not exceed 100%");
+            assert!(
+                late_penalty_bps <= MAX_BPS,
+                "Late penalty must not exceed 100%"
+            );
+            assert!(fee_bps <= max_fee_bps, "Fee must not exceed its ceiling");
+            assert!(
+                fund_deadline <= campaign_start_time,
+                "Funding deadline must not be after the campaign start"
+            );
+            assert!(
+                zero_hashes
+                    .windows(2)
+                    .all(|pair| pair[1] == hash(hash_algo, &pair[0], &pair[0])),
+                "Each zero hash must be the parent of two of the one below"
+            );
 
             let caller = Self::env().caller();
+            // Fail if no one could ever pass `check_owner`
+            assert!(
+                caller != Address::zero(),
+                "Owner must not be the zero address"
+            );
             let asset_contract = AssetHubPrecompileRef::from_addr(asset_contract_address);
+            let mut root_log = Mapping::new();
+            root_log.insert(0, &(root, Self::env().block_timestamp()));
+
+            Self::env().emit_event(OwnershipTransferred {
+                previous: Address::zero(),
+                new: caller,
+            });
 
             Self {
                 asset_contract,
                 root,
+                num_leaves,
                 claimed: Mapping::new(),
+                claimed_bitmap: Mapping::new(),
+                delegates: Mapping::new(),
+                claimed_at: Mapping::new(),
+                claimed_index: Mapping::new(),
                 owner: caller,
+                campaign_start_time,
                 campaign_end_time,
+                hash_algo,
+                lenient_transfers,
+                sweep_delay,
+                metadata_uri,
+                contributions: Mapping::new(),
+                total_contributions: U256::zero(),
+                require_funding_before_start,
+                unit_value,
+                total_claimed: U256::zero(),
+                claimed_nonces: Mapping::new(),
+                funded: false,
+                decimals_factor,
+                sweep_to_burn,
+                claimed_amounts: Mapping::new(),
+                auto_extend_threshold_bps,
+                auto_extend_duration,
+                expected_total,
+                auto_extended: false,
+                max_distribution,
+                shard_roots,
+                tree_claimed: Mapping::new(),
+                pull_payments,
+                pending_withdrawals: Mapping::new(),
+                reserved: U256::zero(),
+                max_batch,
+                used_signatures: Mapping::new(),
+                require_allowlist,
+                allowlist: Mapping::new(),
+                proof_digests: Mapping::new(),
+                claimed_count: 0,
+                allow_funding_after_end,
+                claim_cooldown,
+                last_payout_at: Mapping::new(),
+                root_index: 0,
+                recent_roots: Mapping::new(),
+                operator: Address::zero(),
+                paused: false,
+                receipts: Mapping::new(),
+                payout_overrides: Mapping::new(),
+                initialized: true,
+                vested_claimed: Mapping::new(),
+                root_provider,
+                root_synced_at: None,
+                total_funded: U256::zero(),
+                max_referrer_bps,
+                fee_bps,
+                max_fee_bps,
+                escrows: Mapping::new(),
+                claims_enabled: true,
+                root_log,
+                funding_finalized: false,
+                cancelled: false,
+                grace_period,
+                late_penalty_bps,
+                emit_remaining_balance,
+                claim_times: Mapping::new(),
+                claim_log_len: 0,
+                end_time_timelock,
+                pending_end_time: 0,
+                end_time_effective_at: 0,
+                zero_hashes,
+                transfer_lock: Lazy::new(),
+                owed: Mapping::new(),
+                fund_deadline,
+                tree_receipts: Mapping::new(),
+                asset_receipts: Mapping::new(),
+                multi_claim_indices: Mapping::new(),
+                owed_assets: Mapping::new(),
+                reserved_assets: Mapping::new(),
             }
         }
 
+        /// Create a new Merkle airdrop contract for an Asset Hub asset id.
+        ///
+        /// Instantiates an `AssetHubPrecompile` proxy for `asset_id` from
+        /// `assets_code_hash` and then behaves exactly like [`new`], so
+        /// deployments can reference the asset the way Asset Hub does.
+        ///
+        /// # Arguments
+        /// - `asset_id`: Asset Hub id of the asset to distribute.
+        /// - `assets_code_hash`: code hash of the uploaded `assets` contract.
+        /// - `config`: the campaign's settings, as for [`new`].
+        ///
+        /// # Panics
+        /// - If instantiating the asset proxy fails.
+        /// - In every case [`new`] panics.
+        #[ink(constructor, payable)]
+        pub fn new_with_asset_id(
+            asset_id: AssetId,
+            assets_code_hash: H256,
+            config: CampaignConfig,
+        ) -> Self {
+            let asset_contract = AssetHubPrecompileRef::new(asset_id)
+                .code_hash(assets_code_hash)
+                .endowment(U256::zero())
+                .salt_bytes(None)
+                .instantiate();
+
+            Self::new(asset_contract.to_addr(), config)
+        }
+
+        /// Create an unconfigured campaign to be set up later with [`init`].
+        ///
+        /// For factories that deploy many campaigns from one uploaded code
+        /// hash and configure each one afterwards. The caller becomes the
+        /// owner, other settings take their most permissive defaults, and
+        /// nothing can be claimed until `init` sets a root and end time.
+        #[ink(constructor)]
+        pub fn new_uninitialized() -> Self {
+            // Placeholders that pass `new`'s checks; `init` replaces them.
+            let mut contract = Self::new(
+                Address::zero(),
+                CampaignConfig {
+                    root: [0xff; 32],
+                    campaign_end_time: Self::env().block_timestamp().saturating_add(1),
+                    num_leaves: 1,
+                    ..CampaignConfig::default()
+                },
+            );
+            contract.root = [0u8; 32];
+            contract.root_log.remove(0);
+            // A window that never opens, so nothing is claimable until `init`.
+            contract.campaign_start_time = u64::MAX;
+            contract.campaign_end_time = 0;
+            contract.initialized = false;
+
+            contract
+        }
+
         /// Fund the Merkle airdrop campaign.
         ///
         /// Locks the specified amount of ERC20-compatible tokens
@@ -169,143 +1542,4911 @@ mod merke_airdrop {
         /// - Transfers `total_airdrop_amount` tokens from the caller into this contract.
         /// - Requires the caller to have approved this contract to spend
         ///   at least `total_airdrop_amount` tokens beforehand.
+        /// - Adds `total_airdrop_amount` to the caller's contribution ledger
+        ///   and emits [`Funded`].
         ///
         /// # Errors
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
         /// - [`Error::AmountCannotBeZero`]: if the amount is zero.
+        /// - [`Error::FundingClosed`]: if funding must happen before the
+        ///   campaign starts and `campaign_start_time` has been reached, the
+        ///   `fund_deadline` has passed, or funding has been finalized.
+        /// - [`Error::ClaimPeriodOver`]: if the campaign has ended, unless
+        ///   deployed with `allow_funding_after_end`.
         /// - [`Error::TransferFailed`]: if the token transfer fails.
         #[ink(message)]
         pub fn fund(&mut self, total_airdrop_amount: U256) -> Result<()> {
+            self.check_initialized()?;
             if total_airdrop_amount.is_zero() {
                 return Err(Error::AmountCannotBeZero);
             }
+            if self.funding_finalized
+                || self.fund_deadline_passed()
+                || (self.require_funding_before_start
+                    && self.env().block_timestamp() >= self.campaign_start_time)
+            {
+                return Err(Error::FundingClosed);
+            }
+            if !self.allow_funding_after_end
+                && self.env().block_timestamp() > self.campaign_end_time
+            {
+                return Err(Error::ClaimPeriodOver);
+            }
 
             let caller = self.env().caller();
             let contract = self.env().address();
 
-            let transferred =
-                self.asset_contract
-                    .transferFrom(caller, contract, total_airdrop_amount);
+            let expected =
+                self.expected_balance(|balance| balance.checked_add(total_airdrop_amount));
+            let transferred = self
+                .asset_contract
+                .call_mut()
+                .transferFrom(caller, contract, total_airdrop_amount)
+                .try_invoke();
+            self.check_token_call(transferred, expected)?;
 
-            match transferred {
-                Ok(true) => Ok(()),
-                _ => Err(Error::TransferFailed),
-            }
+            self.funded = true;
+            let contributed = self.funded_by(caller).saturating_add(total_airdrop_amount);
+            self.contributions.insert(caller, &contributed);
+            self.total_contributions = self
+                .total_contributions
+                .saturating_add(total_airdrop_amount);
+            self.total_funded = self.total_funded.saturating_add(total_airdrop_amount);
+
+            self.env().emit_event(Funded {
+                funder: caller,
+                amount: total_airdrop_amount,
+            });
+
+            Ok(())
         }
 
-        /// Claim tokens from the Merkle airdrop.
+        /// Fund the campaign in one transaction using an EIP-2612 permit.
+        ///
+        /// Submits the caller's signed approval of this contract for `amount`
+        /// to the asset, then funds exactly like [`fund`]. Only works with
+        /// assets that implement `permit`; others should use `approve` and
+        /// `fund`.
         ///
         /// # Arguments
-        /// - `value`: claim amount for the recipient.
-        /// - `proof`: Merkle proof for `(recipient, value)`.
-        /// - `index`: leaf index in the Merkle tree.
+        /// - `amount`: number of tokens to deposit.
+        /// - `deadline`: permit expiry, passed to the asset unchanged.
+        /// - `v`, `r`, `s`: the caller's signature over the permit.
         ///
         /// # Errors
-        /// - [`Error::AlreadyClaimed`]: if recipient already claimed.
-        /// - [`Error::InvalidProof`]: if Merkle proof does not validate.
-        /// - [`Error::TransferFailed`]: if token transfer fails.
-        /// - [`Error::ClaimPeriodOver`]: if campaign already ended.
+        /// - [`Error::PermitFailed`]: if the asset rejects the permit, or
+        ///   does not support one.
+        /// - Any error returned by [`fund`].
         #[ink(message)]
-        pub fn claim(&mut self, value: U256, proof: Vec<[u8; 32]>, index: u64) -> Result<()> {
-            self.check_campaign_ongoing()?;
+        pub fn fund_with_permit(
+            &mut self,
+            amount: U256,
+            deadline: U256,
+            v: u8,
+            r: [u8; 32],
+            s: [u8; 32],
+        ) -> Result<()> {
+            let owner = self.env().caller();
+            let spender = self.env().address();
 
-            let recipient = self.env().caller();
-            let already_claimed = self.is_claimed(recipient);
+            let mut asset: ink::contract_ref!(Erc20Permit) =
+                FromAddr::from_addr(self.asset_contract.to_addr());
+            let permitted = asset
+                .call_mut()
+                .permit(owner, spender, amount, deadline, v, r, s)
+                .try_invoke();
+            if !matches!(permitted, Ok(Ok(Ok(())))) {
+                return Err(Error::PermitFailed);
+            }
 
-            if already_claimed {
+            self.fund(amount)
+        }
+
+        /// Top up the campaign with additional tokens.
+        ///
+        /// Alias for [`fund`] that reads more clearly for deposits made after
+        /// the initial funding. Accumulates into the caller's contribution
+        /// ledger and emits [`Funded`] exactly like `fund`.
+        ///
+        /// # Errors
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::AmountCannotBeZero`]: if the amount is zero.
+        /// - [`Error::TransferFailed`]: if the token transfer fails.
+        #[ink(message)]
+        pub fn top_up(&mut self, amount: U256) -> Result<()> {
+            self.fund(amount)
+        }
+
+        /// Claim tokens from the Merkle airdrop.
+        ///
+        /// # Arguments
+        /// - `value`: claim amount for the recipient, as committed in the
+        ///   tree; the payout is `value * decimals_factor`.
+        /// - `proof`: Merkle proof for `(recipient, value)`.
+        /// - `index`: leaf index in the Merkle tree.
+        ///
+        /// # Errors
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::Overflow`]: if scaling `value` by `decimals_factor` overflows.
+        /// - [`Error::AlreadyClaimed`]: if recipient or leaf index already claimed.
+        /// - [`Error::AmountCannotBeZero`]: if the proven leaf commits to zero.
+        /// - [`Error::NotAllowed`]: if the campaign requires an allowlist and
+        ///   the recipient is not on it.
+        /// - [`Error::IndexOutOfRange`]: if `index` is not below `num_leaves`.
+        /// - [`Error::DistributionCapExceeded`]: if paying `value` would push
+        ///   `total_claimed` past `max_distribution`.
+        /// - [`Error::InvalidProof`]: if Merkle proof does not validate.
+        /// - [`Error::CampaignCancelled`]: if the campaign has been cancelled.
+        /// - [`Error::ClaimPeriodNotStarted`]: if campaign has not started yet.
+        /// - [`Error::ClaimPeriodOver`]: if campaign already ended, including
+        ///   its `grace_period`. Claims during the grace period succeed, but
+        ///   pay `late_penalty_bps` less.
+        ///
+        /// A verified claim whose token transfer fails still succeeds: the
+        /// leaf is marked claimed and the payout is kept as [`owed`] to the
+        /// recipient, who collects it with [`retry_claim`] once the asset
+        /// accepts the transfer.
+        #[ink(message)]
+        pub fn claim(&mut self, value: U256, proof: Vec<[u8; 32]>, index: u64) -> Result<()> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
+
+            self.validate_claim(recipient, value, &proof, index)?;
+            self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)
+        }
+
+        /// Claim tokens with the proof packed into a single byte string.
+        ///
+        /// Same as [`claim`], but `proof_bytes` holds the siblings back to
+        /// back, which encodes smaller than `Vec<[u8; 32]>` for deep trees.
+        ///
+        /// # Errors
+        /// - [`Error::InvalidProof`]: if `proof_bytes` is not a multiple of 32
+        ///   bytes long.
+        /// - Otherwise the same errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_packed(
+            &mut self,
+            value: U256,
+            proof_bytes: Vec<u8>,
+            index: u64,
+        ) -> Result<()> {
+            let proof = unpack_proof(&proof_bytes).ok_or(Error::InvalidProof)?;
+
+            self.claim(value, proof, index)
+        }
+
+        /// Claim a share of everything the campaign raised.
+        ///
+        /// For campaigns whose total is not known when the tree is built: the
+        /// leaf commits to `share_bps` instead of an amount, hashed as
+        /// `H(LEAF_SHARE || recipient || share_bps)` with the share as 2
+        /// big-endian bytes, and pays `total_funded * share_bps / MAX_BPS`.
+        /// Claims open once the owner calls [`finalize_funding`], so every
+        /// share is taken from the same total. The tree's shares should add up
+        /// to at most [`MAX_BPS`].
+        ///
+        /// # Errors
+        /// - [`Error::FundingNotFinalized`]: if funding is still open.
+        /// - [`Error::AmountCannotBeZero`]: if the share comes to nothing.
+        /// - Otherwise the same errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_share(
+            &mut self,
+            share_bps: u16,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<()> {
+            self.sync_root()?;
+            if !self.funding_finalized {
+                return Err(Error::FundingNotFinalized);
+            }
+            let recipient = self.env().caller();
+
+            let leaf = self.tagged_leaf(LEAF_SHARE, recipient, &share_bps.to_be_bytes());
+            self.validate_leaf(recipient, leaf, U256::from(share_bps), &proof, index)?;
+
+            let amount = self
+                .total_funded
+                .checked_mul(U256::from(share_bps))
+                .ok_or(Error::Overflow)?
+                / U256::from(MAX_BPS);
+            if amount.is_zero() {
+                return Err(Error::AmountCannotBeZero);
+            }
+
+            self.settle_claim(recipient, amount, index, &proof)
+        }
+
+        /// Claim several leaves of the caller's at once with one multiproof.
+        ///
+        /// `leaves_values` holds `(value, index)` for each leaf, in ascending
+        /// index order, and [`verify_multiproof`] checks them all against the
+        /// current root with the shared `proof` and `proof_flags`. The leaves
+        /// are marked claimed and their sum paid as a single payout, after
+        /// which the caller counts as claimed like after [`claim`].
+        ///
+        /// # Errors
+        /// - [`Error::BatchTooLarge`]: if there are more than `max_batch` leaves.
+        /// - [`Error::AmountCannotBeZero`]: if any leaf commits to zero.
+        /// - [`Error::InvalidMultiproof`]: if the multiproof does not verify.
+        /// - Otherwise the same errors as [`claim`], except [`Error::InvalidProof`].
+        #[ink(message)]
+        pub fn claim_multi(
+            &mut self,
+            leaves_values: Vec<(U256, u64)>,
+            proof: Vec<[u8; 32]>,
+            proof_flags: Vec<bool>,
+        ) -> Result<()> {
+            self.sync_root()?;
+            if leaves_values.len() > self.max_batch as usize {
+                return Err(Error::BatchTooLarge);
+            }
+            let recipient = self.env().caller();
+
+            self.check_campaign_ongoing()?;
+            self.check_allowed(recipient)?;
+            if self.is_claimed(recipient) {
                 return Err(Error::AlreadyClaimed);
             }
 
-            let recipient_bytes = recipient.as_bytes();
-            let value_bytes = value.to_big_endian();
-            let leaf = hash(recipient_bytes, &value_bytes);
-            let verified = verify_proof(leaf, &proof, index, self.root);
+            let mut leaves = Vec::with_capacity(leaves_values.len());
+            let mut total = U256::zero();
+            for &(value, index) in &leaves_values {
+                if value.is_zero() {
+                    return Err(Error::AmountCannotBeZero);
+                }
+                self.check_index(index)?;
+                if self.is_claimed_index(index) {
+                    return Err(Error::AlreadyClaimed);
+                }
+
+                leaves.push((index, self.leaf(recipient, value)));
+                total = total
+                    .checked_add(self.scale_amount(value)?)
+                    .ok_or(Error::Overflow)?;
+            }
 
+            // As with `check_proof`, only a single-leaf tree needs no proof.
+            let verified = (!proof_flags.is_empty() || self.num_leaves == 1)
+                && verify_multiproof(
+                    self.hash_algo,
+                    &leaves,
+                    &proof,
+                    &proof_flags,
+                    self.active_root()?,
+                );
             if !verified {
-                return Err(Error::InvalidProof);
+                return Err(Error::InvalidMultiproof);
             }
 
-            self.claimed.insert(recipient, &true);
+            for &(_, index) in &leaves {
+                self.set_claimed_index(index);
+                self.count_claim();
+            }
+            let indices: Vec<u64> = leaves.iter().map(|&(index, _)| index).collect();
+            self.multi_claim_indices.insert(recipient, &indices);
+            self.record_claim(recipient, total, leaves[0].0, &proof)?;
+
+            self.pay_out(recipient, total)
+        }
+
+        /// Claim tokens and pay `referrer` a bonus on top.
+        ///
+        /// Pays the claim exactly like [`claim`], then pays
+        /// `payout * referrer_bps / MAX_BPS` to `referrer`. The bonus comes out
+        /// of the contract's unreserved balance and counts towards
+        /// `total_claimed` and `max_distribution`, so it reduces what is left
+        /// for other recipients; campaigns using referrals should fund for it.
+        /// The bonus is paid the way claims are: credited for
+        /// [`withdraw`] under pull payments, and kept for the referrer
+        /// to collect with [`retry_claim`] if the asset refuses it.
+        ///
+        /// # Errors
+        /// - [`Error::ZeroReferrer`]: if `referrer` is the zero address.
+        /// - [`Error::SelfReferral`]: if `referrer` is the caller.
+        /// - [`Error::ReferralTooLarge`]: if `referrer_bps` exceeds
+        ///   `max_referrer_bps`.
+        /// - [`Error::InsufficientBalance`]: if the bonus exceeds the
+        ///   contract's unreserved balance.
+        /// - Otherwise the same errors as [`claim`], for the claim or the bonus.
+        #[ink(message)]
+        pub fn claim_with_referrer(
+            &mut self,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+            referrer: Address,
+            referrer_bps: u16,
+        ) -> Result<()> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
+            if referrer == Address::zero() {
+                return Err(Error::ZeroReferrer);
+            }
+            if referrer == recipient {
+                return Err(Error::SelfReferral);
+            }
+            if referrer_bps > self.max_referrer_bps {
+                return Err(Error::ReferralTooLarge);
+            }
+
+            self.validate_claim(recipient, value, &proof, index)?;
+            let amount = self.scale_amount(value)?;
+            self.settle_claim(recipient, amount, index, &proof)?;
 
-            let transferred = self.asset_contract.transfer(recipient, value);
+            let bonus = amount
+                .checked_mul(U256::from(referrer_bps))
+                .ok_or(Error::Overflow)?
+                / U256::from(MAX_BPS);
+            if bonus.is_zero() {
+                return Ok(());
+            }
 
-            if transferred.is_err() {
-                return Err(Error::TransferFailed);
+            if self.unreserved_balance() < bonus {
+                return Err(Error::InsufficientBalance);
             }
 
-            self.env().emit_event(Claimed { recipient, value });
+            self.add_claimed(bonus)?;
+            self.deliver(referrer, bonus)?;
+
+            self.env().emit_event(ReferralPaid {
+                referrer,
+                recipient,
+                amount: bonus,
+            });
 
             Ok(())
         }
 
-        /// Sweep unclaimed tokens after the campaign has ended.
+        /// Claim tokens with a proof against the root numbered `root_index`.
         ///
-        /// Transfers the remaining balance from the contract back to the owner.
+        /// Like [`claim`], but also accepts the roots replaced by the last
+        /// [`ROOT_HISTORY_SIZE`] calls to [`update_root`], for up to
+        /// [`ROOT_GRACE_PERIOD`] after each was replaced, so a claim signed
+        /// just before an update still goes through. The current root is
+        /// first refreshed from `root_provider`, if set, exactly as in
+        /// [`claim`].
         ///
         /// # Errors
-        /// - [`Error::Unauthorized`]: if caller is not the owner.
-        /// - [`Error::ClaimPeriodActive`]: if the claim window is still open.
+        /// - [`Error::RootExpired`]: if `root_index` is not the current root
+        ///   or a replaced root still within its grace period.
+        /// - Any error returned by [`claim`].
         #[ink(message)]
-        pub fn sweep_unclaimed(&mut self) -> Result<()> {
-            self.check_owner()?;
-            self.check_campaign_ended()?;
+        pub fn claim_against_root(
+            &mut self,
+            root_index: u32,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<()> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
+            let root = self.root_at(root_index)?;
 
-            let contract = self.env().address();
+            let leaf = self.leaf(recipient, value);
+            self.validate_leaf_under(root, recipient, leaf, value, &proof, index)?;
+            self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)
+        }
+
+        /// Claim tokens from a tree whose leaves commit to allocation units.
+        ///
+        /// The leaf is `hash(LEAF_UNITS || recipient, units)` with `units` as
+        /// an 8-byte big-endian integer; the payout is `units * unit_value`.
+        /// `unit_value` is already in the asset's smallest unit, so
+        /// `decimals_factor` is not applied.
+        ///
+        /// # Arguments
+        /// - `units`: number of allocation units for the recipient.
+        /// - `proof`: Merkle proof for `(recipient, units)`.
+        /// - `index`: leaf index in the Merkle tree.
+        ///
+        /// # Errors
+        /// - [`Error::Overflow`]: if `units * unit_value` overflows.
+        /// - Otherwise the same errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_units(&mut self, units: u64, proof: Vec<[u8; 32]>, index: u64) -> Result<()> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
+            let value = self
+                .unit_value
+                .checked_mul(U256::from(units))
+                .ok_or(Error::Overflow)?;
+
+            let leaf = self.tagged_leaf(LEAF_UNITS, recipient, &units.to_be_bytes());
+            self.validate_leaf(recipient, leaf, value, &proof, index)?;
+            self.settle_claim(recipient, value, index, &proof)
+        }
+
+        /// Attempt a claim without reverting on ineligibility.
+        ///
+        /// Behaves like [`claim`], but reports why a claim was rejected in the
+        /// returned [`ClaimOutcome`] instead of reverting, so front-ends and
+        /// support tooling can tell failure modes apart. Nothing is written to
+        /// storage unless the outcome is [`ClaimOutcome::Claimed`].
+        ///
+        /// # Errors
+        /// - Any other error [`claim`] returns, which still reverts.
+        #[ink(message)]
+        pub fn try_claim(
+            &mut self,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<ClaimOutcome> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
+
+            let outcome = match self.validate_claim(recipient, value, &proof, index) {
+                Ok(()) => ClaimOutcome::Claimed,
+                Err(Error::AlreadyClaimed) => ClaimOutcome::AlreadyClaimed,
+                Err(Error::InvalidProof | Error::IndexOutOfRange) => ClaimOutcome::InvalidProof,
+                Err(Error::ClaimPeriodNotStarted) => ClaimOutcome::NotStarted,
+                Err(Error::ClaimPeriodOver) => ClaimOutcome::PeriodOver,
+                Err(Error::NotAllowed) => ClaimOutcome::NotAllowed,
+                Err(error) => return Err(error),
+            };
+
+            if outcome == ClaimOutcome::Claimed {
+                self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)?;
+            }
+
+            Ok(outcome)
+        }
+
+        /// Authorize `delegate` to claim on the caller's behalf.
+        ///
+        /// The delegate may then call [`claim_for`] with the caller's leaf;
+        /// tokens are still paid to the caller. Setting a new delegate
+        /// replaces the previous one.
+        #[ink(message)]
+        pub fn set_delegate(&mut self, delegate: Address) {
             let caller = self.env().caller();
-            let balance = self.asset_contract.balanceOf(contract);
+            self.delegates.insert(caller, &delegate);
+        }
+
+        /// Claim tokens with an explicit direction for every proof step.
+        ///
+        /// `flags[i]` is `true` when `proof[i]` is the left sibling, for
+        /// tooling that emits directions instead of a leaf index. The
+        /// directions spell out the leaf's position bit by bit, so the index
+        /// used for double-claim protection is derived from them.
+        ///
+        /// # Arguments
+        /// - `value`: claim amount for the recipient, as committed in the tree.
+        /// - `proof`: Merkle proof for `(recipient, value)`.
+        /// - `flags`: one direction per proof element, leaf level first.
+        ///
+        /// # Errors
+        /// - [`Error::InvalidProof`]: if `flags` and `proof` differ in length,
+        ///   hold more than 64 steps, or the proof does not validate.
+        /// - Otherwise the same errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_with_flags(
+            &mut self,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            flags: Vec<bool>,
+        ) -> Result<()> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
+            if flags.len() != proof.len() || flags.len() > u64::BITS as usize {
+                return Err(Error::InvalidProof);
+            }
+
+            let index = flags
+                .iter()
+                .rev()
+                .fold(0u64, |index, &left| (index << 1) | u64::from(left));
+
+            self.validate_claim(recipient, value, &proof, index)?;
+            self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)
+        }
+
+        /// Claim tokens from one of the shard trees.
+        ///
+        /// Verifies the usual `(recipient, value)` leaf against
+        /// `shard_roots[tree_id]` instead of `root`, with the same proof rules
+        /// as [`claim`] applied to the shard's own leaf count. Double-claim
+        /// protection is kept per shard, keyed on `(tree_id, recipient)`, and
+        /// so is the receipt, returned by [`tree_claim_receipt`]. Shard claims
+        /// do not count towards [`claimed_count`], which tracks the main tree.
+        ///
+        /// # Arguments
+        /// - `tree_id`: position of the shard root in `shard_roots`.
+        /// - `value`: claim amount for the recipient, as committed in the tree.
+        /// - `proof`: Merkle proof for `(recipient, value)` in that shard.
+        /// - `index`: leaf index in that shard.
+        ///
+        /// # Errors
+        /// - [`Error::UnknownTree`]: if there is no shard with `tree_id`.
+        /// - [`Error::IndexOutOfRange`]: if `index` is not below the shard's
+        ///   leaf count.
+        /// - [`Error::AlreadyClaimed`]: if the caller already claimed from it.
+        /// - Otherwise the same errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_from_tree(
+            &mut self,
+            tree_id: u32,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<()> {
+            let recipient = self.env().caller();
+            let (root, num_leaves) = self.shard(tree_id)?;
+
+            self.check_campaign_ongoing()?;
+            self.check_allowed(recipient)?;
+            if index >= num_leaves {
+                return Err(Error::IndexOutOfRange);
+            }
+            if self.is_claimed_in_tree(tree_id, recipient) {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            let leaf = self.leaf(recipient, value);
+            self.check_proof(root, num_leaves, leaf, value, &proof, index)?;
+
+            let value = self.scale_amount(value)?;
+            self.tree_claimed.insert((tree_id, recipient), &true);
+            self.tree_receipts
+                .insert((tree_id, recipient), &self.receipt(value, index)?);
+            self.pay_out(recipient, value)
+        }
+
+        /// Claim one of several allocations committed to the caller.
+        ///
+        /// The leaf is `hash(LEAF_NONCE || recipient, nonce || value)` with
+        /// `nonce` as an 8-byte big-endian integer, so a tree may hold several
+        /// entries (e.g. reward categories) for one address. Each
+        /// `(recipient, nonce)` pair is double-claim protected on its own and
+        /// does not affect [`claim`].
+        ///
+        /// # Arguments
+        /// - `value`: claim amount for this entry, as committed in the tree.
+        /// - `nonce`: entry number distinguishing the recipient's allocations.
+        /// - `proof`: Merkle proof for `(recipient, nonce, value)`.
+        /// - `index`: leaf index in the Merkle tree.
+        ///
+        /// # Errors
+        /// - [`Error::AlreadyClaimed`]: if this nonce or leaf index was claimed.
+        /// - Otherwise the same errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_with_nonce(
+            &mut self,
+            value: U256,
+            nonce: u64,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<()> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
 
-            let transferred = self.asset_contract.transfer(caller, balance);
+            let leaf = self.nonce_leaf(recipient, nonce, value);
+            self.validate_nonce_leaf(recipient, nonce, leaf, value, &proof, index)?;
 
-            if transferred.is_err() {
-                return Err(Error::TransferFailed);
+            // Only the part not already paid out by `claim_all_vested`.
+            let remaining = value.saturating_sub(self.vested_claimed(recipient, nonce));
+            self.claimed_nonces.insert((recipient, nonce), &true);
+            self.pay_claim(recipient, self.scale_amount(remaining)?, index)
+        }
+
+        /// Claim the currently vested part of several nonce entries at once.
+        ///
+        /// Each entry vests linearly with [`vested_bps`]; this pays the part
+        /// of each that has vested but not been paid yet, as one payout.
+        /// Entries with nothing new vested, or already fully paid, are
+        /// skipped rather than failing the call. An entry that is fully
+        /// vested and paid is marked claimed like with [`claim_with_nonce`].
+        ///
+        /// # Returns
+        /// The total amount paid, after scaling by `decimals_factor`.
+        ///
+        /// # Errors
+        /// - [`Error::BatchTooLarge`]: if `entries` has more than `max_batch`
+        ///   entries.
+        /// - Otherwise the same errors as [`claim_with_nonce`], except
+        ///   [`Error::AlreadyClaimed`].
+        #[ink(message)]
+        pub fn claim_all_vested(&mut self, entries: Vec<NonceClaim>) -> Result<U256> {
+            self.sync_root()?;
+            if entries.len() > self.max_batch as usize {
+                return Err(Error::BatchTooLarge);
             }
 
-            Ok(())
+            let recipient = self.env().caller();
+            let vested_bps = U256::from(self.vested_bps());
+            let mut total = U256::zero();
+            for NonceClaim {
+                value,
+                nonce,
+                proof,
+                index,
+            } in entries
+            {
+                let leaf = self.nonce_leaf(recipient, nonce, value);
+                match self.validate_nonce_leaf(recipient, nonce, leaf, value, &proof, index) {
+                    Err(Error::AlreadyClaimed) => continue,
+                    result => result?,
+                }
+
+                let vested =
+                    value.checked_mul(vested_bps).ok_or(Error::Overflow)? / U256::from(MAX_BPS);
+                let paid = self.vested_claimed(recipient, nonce);
+                if vested <= paid {
+                    continue;
+                }
+
+                if vested == value {
+                    self.claimed_nonces.insert((recipient, nonce), &true);
+                    self.set_claimed_index(index);
+                    self.count_claim();
+                }
+                self.vested_claimed.insert((recipient, nonce), &vested);
+                total = total
+                    .checked_add(self.scale_amount(vested - paid)?)
+                    .ok_or(Error::Overflow)?;
+            }
+
+            if !total.is_zero() {
+                self.pay_out(recipient, total)?;
+            }
+
+            Ok(total)
         }
 
-        /// Get the token asset id of the asset contract.
+        /// Claim tokens for `recipient` as their registered delegate.
+        ///
+        /// The leaf is built from `recipient`, tokens are paid to `recipient`
+        /// and double-claim protection stays keyed on `recipient`.
+        ///
+        /// # Arguments
+        /// - `recipient`: address committed to in the leaf.
+        /// - `value`: claim amount for the recipient.
+        /// - `proof`: Merkle proof for `(recipient, value)`.
+        /// - `index`: leaf index in the Merkle tree.
+        ///
+        /// # Errors
+        /// - [`Error::NotDelegate`]: if the caller is not `recipient`'s delegate.
+        /// - Otherwise the same errors as [`claim`].
         #[ink(message)]
-        pub fn asset_id(&self) -> AssetId {
-            self.asset_contract.assetId()
+        pub fn claim_for(
+            &mut self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<()> {
+            self.sync_root()?;
+            if self.delegates.get(recipient) != Some(self.env().caller()) {
+                return Err(Error::NotDelegate);
+            }
+
+            self.validate_claim(recipient, value, &proof, index)?;
+            self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)
         }
 
-        /// Get the Merkle root.
+        /// Claim tokens for `recipient`, submitted by anyone.
+        ///
+        /// Lets a relayer or any third party push a claim through without a
+        /// delegation or signature: the leaf is built from `recipient`,
+        /// tokens are paid to `recipient` and double-claim protection stays
+        /// keyed on `recipient`, so the caller can only ever deliver the
+        /// tokens to the address the tree names.
+        ///
+        /// # Arguments
+        /// - `recipient`: address committed to in the leaf.
+        /// - `value`: claim amount for the recipient.
+        /// - `proof`: Merkle proof for `(recipient, value)`.
+        /// - `index`: leaf index in the Merkle tree.
+        ///
+        /// # Errors
+        /// - Same as [`claim`].
         #[ink(message)]
-        pub fn root(&self) -> [u8; 32] {
-            self.root
+        pub fn claim_to_leaf(
+            &mut self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<()> {
+            self.sync_root()?;
+
+            self.validate_claim(recipient, value, &proof, index)?;
+            self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)
         }
 
-        /// Check if a recipient has already claimed.
+        /// Claim for `recipient` using their signed authorization.
+        ///
+        /// Lets a relayer pay the fees for a recipient who signed
+        /// `keccak256(CLAIM_SIG_DOMAIN || contract || recipient || value ||
+        /// index || deadline)` with the key of `recipient`, where `contract`
+        /// is this contract's address, `value` is 32 and `index` and
+        /// `deadline` are 8 big-endian bytes. Binding the digest to the
+        /// contract keeps a signature for one campaign from being replayed
+        /// against another with an overlapping tree. Tokens are paid to
+        /// `recipient`. Each digest can be used once; keying on the digest
+        /// rather than the signature bytes also stops malleated copies of a
+        /// signature from being replayed.
+        ///
+        /// # Arguments
+        /// - `recipient`: address committed to in the leaf, and the signer.
+        /// - `value`: claim amount, as committed in the tree.
+        /// - `proof`: Merkle proof for `(recipient, value)`.
+        /// - `index`: leaf index in the Merkle tree.
+        /// - `deadline`: last block timestamp at which the signature is valid.
+        /// - `signature`: 65-byte recoverable ECDSA signature over the digest.
+        ///
+        /// # Errors
+        /// - [`Error::SignatureExpired`]: if `deadline` has passed.
+        /// - [`Error::SignatureAlreadyUsed`]: if the digest was already used.
+        /// - [`Error::InvalidSignature`]: if the signature does not recover to
+        ///   `recipient`.
+        /// - Otherwise the same errors as [`claim`].
         #[ink(message)]
-        pub fn is_claimed(&self, recipient: Address) -> bool {
-            self.claimed.get(recipient).unwrap_or(false)
+        pub fn claim_with_sig(
+            &mut self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            self.sync_root()?;
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::SignatureExpired);
+            }
+
+            let mut payload = Vec::with_capacity(CLAIM_SIG_DOMAIN.len() + 20 + 20 + 32 + 8 + 8);
+            payload.extend_from_slice(CLAIM_SIG_DOMAIN);
+            payload.extend_from_slice(self.env().address().as_bytes());
+            payload.extend_from_slice(recipient.as_bytes());
+            payload.extend_from_slice(&value.to_big_endian());
+            payload.extend_from_slice(&index.to_be_bytes());
+            payload.extend_from_slice(&deadline.to_be_bytes());
+            let digest = hash_with::<Keccak256>(&payload);
+
+            if self.used_signatures.get(digest).unwrap_or(false) {
+                return Err(Error::SignatureAlreadyUsed);
+            }
+
+            let signer = self
+                .env()
+                .ecdsa_recover(&signature, &digest)
+                .and_then(|public_key| self.env().ecdsa_to_eth_address(&public_key))
+                .map_err(|_| Error::InvalidSignature)?;
+            if Address::from(signer) != recipient {
+                return Err(Error::InvalidSignature);
+            }
+
+            self.validate_claim(recipient, value, &proof, index)?;
+
+            self.used_signatures.insert(digest, &true);
+            self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)
         }
 
-        /// Internal: ensure caller is owner.
-        fn check_owner(&self) -> Result<()> {
-            if self.owner != self.env().caller() {
-                return Err(Error::Unauthorized);
+        /// Claim several allocations in one call.
+        ///
+        /// Each entry is processed like [`claim_for`], except that the caller
+        /// may also be the entry's recipient. Tokens are paid to each
+        /// recipient, and the whole batch reverts if any entry fails.
+        ///
+        /// # Errors
+        /// - [`Error::BatchTooLarge`]: if `claims` has more than `max_batch`
+        ///   entries.
+        /// - [`Error::NotDelegate`]: if the caller is neither an entry's
+        ///   recipient nor their delegate.
+        /// - Otherwise the same errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_batch(&mut self, claims: Vec<BatchClaim>) -> Result<()> {
+            self.sync_root()?;
+            if claims.len() > self.max_batch as usize {
+                return Err(Error::BatchTooLarge);
+            }
+
+            let caller = self.env().caller();
+            for claim in claims {
+                let BatchClaim {
+                    recipient,
+                    value,
+                    proof,
+                    index,
+                } = claim;
+                if recipient != caller && self.delegates.get(recipient) != Some(caller) {
+                    return Err(Error::NotDelegate);
+                }
+
+                self.validate_claim(recipient, value, &proof, index)?;
+                self.settle_claim(recipient, self.scale_amount(value)?, index, &proof)?;
             }
 
             Ok(())
         }
 
-        /// Internal: ensure campaign has not yet ended.
-        fn check_campaign_ongoing(&self) -> Result<()> {
-            if self.env().block_timestamp() > self.campaign_end_time {
-                return Err(Error::ClaimPeriodOver);
+        /// Claim several Asset Hub assets committed to one leaf.
+        ///
+        /// The leaf is
+        /// `hash(LEAF_MULTI_ASSET || recipient, id || amount || ...)`, with
+        /// each [`AssetAmount`] as a 4-byte big-endian id and a 32-byte
+        /// big-endian amount, in the order committed off-chain. Each amount is
+        /// paid as-is through the asset's ERC20 precompile and emits
+        /// [`AssetClaimed`]. Every amount is net of the claim fee and any late
+        /// penalty, and the claim is subject to the same cooldown as
+        /// [`claim`]. The campaign's own asset, matched by [`asset_id`], is
+        /// instead scaled by `decimals_factor` and paid like [`claim`], so it
+        /// counts towards `total_claimed` and `max_distribution` and a refused
+        /// transfer is kept for [`retry_claim`]. A refused transfer of any
+        /// other asset does not revert the claim: the amount is reported
+        /// unpaid in [`asset_receipt`] and stays reserved in the contract for
+        /// [`retry_asset_claim`].
+        ///
+        /// # Returns
+        /// Whether each entry of `assets` was paid, in order.
+        ///
+        /// # Errors
+        /// - [`Error::AmountCannotBeZero`]: if `assets` is empty or every
+        ///   amount in it is zero.
+        /// - [`Error::Paused`]: if claims are paused.
+        /// - [`Error::ClaimsDisabled`]: if the owner has disabled claims.
+        /// - [`Error::CampaignCancelled`]: if the campaign has been cancelled.
+        /// - [`Error::Cooldown`]: if the caller is still in their cooldown.
+        /// - [`Error::Reentrant`]: if called back from another transfer.
+        /// - Otherwise the same validation errors as [`claim`].
+        #[ink(message)]
+        pub fn claim_multi_asset(
+            &mut self,
+            assets: Vec<AssetAmount>,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<Vec<bool>> {
+            self.sync_root()?;
+            if assets.is_empty() {
+                return Err(Error::AmountCannotBeZero);
             }
 
-            Ok(())
+            let recipient = self.env().caller();
+            self.check_payout(recipient)?;
+            let leaf = self.multi_asset_leaf(recipient, &assets);
+            let value = assets
+                .iter()
+                .fold(U256::zero(), |sum, asset| sum.saturating_add(asset.amount));
+            self.validate_leaf(recipient, leaf, value, &proof, index)?;
+
+            self.claimed.insert(recipient, &true);
+            self.proof_digests
+                .insert(recipient, &hash_with::<Keccak256>(&proof.concat()));
+            self.claimed_at
+                .insert(recipient, &self.env().block_timestamp());
+            self.claimed_index.insert(recipient, &index);
+            self.set_claimed_index(index);
+            self.count_claim();
+
+            // The campaign's own asset is paid once, after the loop, so
+            // repeated entries of it do not trip the cooldown `pay_out` sets.
+            let own_asset = self.asset_id();
+            let to = self.payout_address(recipient);
+            let mut own_amount = U256::zero();
+            let mut payouts = Vec::with_capacity(assets.len());
+            for AssetAmount { id, amount } in assets {
+                if id == own_asset {
+                    own_amount = own_amount.checked_add(amount).ok_or(Error::Overflow)?;
+                    payouts.push(AssetPayout {
+                        id,
+                        amount: self.net_payout(self.scale_amount(amount)?)?,
+                        paid: true,
+                    });
+                    continue;
+                }
+
+                let amount = self.net_payout(amount)?;
+                let paid = match self.transfer_asset_out(id, to, amount) {
+                    Ok(()) => true,
+                    Err(Error::TransferFailed) => false,
+                    Err(error) => return Err(error),
+                };
+                if paid {
+                    self.env().emit_event(AssetClaimed {
+                        recipient,
+                        id,
+                        amount,
+                    });
+                } else {
+                    let owed = self.owed_asset(recipient, id).saturating_add(amount);
+                    self.owed_assets.insert((recipient, id), &owed);
+                    let reserved = self.reserved_asset(id).saturating_add(amount);
+                    self.reserved_assets.insert(id, &reserved);
+                }
+                payouts.push(AssetPayout { id, amount, paid });
+            }
+
+            if !own_amount.is_zero() {
+                self.pay_out(recipient, self.scale_amount(own_amount)?)?;
+            }
+            self.last_payout_at
+                .insert(recipient, &self.env().block_timestamp());
+
+            let paid = payouts.iter().map(|payout| payout.paid).collect();
+            self.asset_receipts.insert(recipient, &payouts);
+
+            Ok(paid)
         }
 
-        /// Internal: ensure campaign has ended.
-        fn check_campaign_ended(&self) -> Result<()> {
-            if self.env().block_timestamp() <= self.campaign_end_time {
-                return Err(Error::ClaimPeriodActive);
+        /// Reverse a recent claim so the recipient may claim again.
+        ///
+        /// Intended for a recipient who claimed to a wrong or compromised
+        /// destination. Only claims made within [`CLAIM_REVERSAL_WINDOW`]
+        /// can be reversed, and only once their payout has left the contract:
+        /// a claim still awaiting [`withdraw`], [`retry_claim`] or
+        /// [`withdraw_escrow`] cannot be. The claim's amount is taken back
+        /// out of `total_claimed` and [`claimed_amount`], and its cooldown is
+        /// cleared. A [`claim_multi`] claim releases every leaf it covered.
+        ///
+        /// **Note:** This does not recover the tokens already transferred;
+        /// it only re-enables eligibility. The owner should only reverse a
+        /// claim once the tokens have been returned to the contract.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::NotClaimed`]: if `recipient` has not claimed.
+        /// - [`Error::ReversalWindowClosed`]: if the claim is too old.
+        /// - [`Error::PayoutOutstanding`]: if the claim's payout is still
+        ///   pending, owed or escrowed.
+        #[ink(message)]
+        pub fn reverse_claim(&mut self, recipient: Address) -> Result<()> {
+            self.check_owner()?;
+
+            let (Some(claimed_at), Some(index)) = (
+                self.claimed_at.get(recipient),
+                self.claimed_index.get(recipient),
+            ) else {
+                return Err(Error::NotClaimed);
+            };
+
+            let reversible_until = claimed_at.saturating_add(CLAIM_REVERSAL_WINDOW);
+            if self.env().block_timestamp() > reversible_until {
+                return Err(Error::ReversalWindowClosed);
+            }
+
+            let outstanding = !self.pending_withdrawal(recipient).is_zero()
+                || !self.owed(recipient).is_zero()
+                || self.escrows.contains(recipient)
+                || self
+                    .asset_receipts
+                    .get(recipient)
+                    .is_some_and(|payouts| payouts.iter().any(|payout| !payout.paid));
+            if outstanding {
+                return Err(Error::PayoutOutstanding);
+            }
+
+            if let Some(receipt) = self.receipts.get(recipient) {
+                self.total_claimed = self
+                    .total_claimed
+                    .saturating_sub(receipt.amount.saturating_add(receipt.withheld));
+                let claimed_amount = self
+                    .claimed_amount(recipient)
+                    .saturating_sub(receipt.amount);
+                self.claimed_amounts.insert(recipient, &claimed_amount);
+            }
+
+            self.claimed.remove(recipient);
+            self.claimed_at.remove(recipient);
+            self.claimed_index.remove(recipient);
+            self.receipts.remove(recipient);
+            self.last_payout_at.remove(recipient);
+
+            let indices = self
+                .multi_claim_indices
+                .take(recipient)
+                .unwrap_or_else(|| Vec::from([index]));
+            for &index in &indices {
+                self.clear_claimed_index(index);
             }
+            self.claimed_count = self.claimed_count.saturating_sub(indices.len() as u64);
+
+            self.env().emit_event(ClaimReversed { recipient });
 
             Ok(())
         }
+
+        /// Sweep unclaimed tokens after the campaign has ended.
+        ///
+        /// Transfers the remaining balance from the contract back to the owner,
+        /// or to [`BURN_ADDRESS`] if the campaign was deployed with
+        /// `sweep_to_burn`. Only allowed once [`sweep_available_at`] has passed.
+        ///
+        /// If less than `auto_extend_threshold_bps` of `expected_total` has
+        /// been claimed, the first sweep instead reopens claims for
+        /// `auto_extend_duration`, emits [`CampaignAutoExtended`], and
+        /// transfers nothing; sweeping is possible again once that ends.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::ClaimPeriodActive`]: if the claim window or the sweep
+        ///   delay after it is still running.
+        /// - [`Error::NothingToSweep`]: if the contract's balance is zero.
+        #[ink(message)]
+        pub fn sweep_unclaimed(&mut self) -> Result<()> {
+            self.check_owner()?;
+            self.check_sweep_available()?;
+            if self.auto_extend()? {
+                return Ok(());
+            }
+
+            let to = if self.sweep_to_burn {
+                BURN_ADDRESS
+            } else {
+                self.env().caller()
+            };
+            self.sweep_balance(to)
+        }
+
+        /// Sweep unclaimed tokens to an arbitrary address.
+        ///
+        /// Same as [`sweep_unclaimed`], but sends the remaining balance to
+        /// `to` (e.g. a treasury multisig) instead of the owner, saving a
+        /// second transfer. Subject to the same automatic extension.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::ClaimPeriodActive`]: if the claim window or the sweep
+        ///   delay after it is still running.
+        /// - [`Error::BurnRequired`]: if the campaign sweeps to
+        ///   [`BURN_ADDRESS`]; use [`sweep_unclaimed`] instead.
+        /// - [`Error::NothingToSweep`]: if the contract's balance is zero.
+        #[ink(message)]
+        pub fn sweep_to(&mut self, to: Address) -> Result<()> {
+            self.check_owner()?;
+            self.check_sweep_available()?;
+            if self.sweep_to_burn {
+                return Err(Error::BurnRequired);
+            }
+            if self.auto_extend()? {
+                return Ok(());
+            }
+
+            self.sweep_balance(to)
+        }
+
+        /// Sweep the contract's balance of each asset in `assets` after the
+        /// campaign has ended.
+        ///
+        /// For multi-asset campaigns paid through [`claim_multi_asset`]. Each
+        /// Asset Hub asset is read and transferred through its ERC20 precompile
+        /// to the owner, or to [`BURN_ADDRESS`] if the campaign sweeps to burn,
+        /// emitting a [`Swept`] per non-empty asset. The campaign's own asset,
+        /// matched by [`asset_id`], goes through `asset_contract` like
+        /// [`sweep_unclaimed`] instead, so claims still awaiting [`withdraw`],
+        /// escrows and owed payouts stay reserved. Other assets keep what is
+        /// owed through [`retry_asset_claim`]. Assets with nothing to sweep
+        /// are skipped rather than failing the call; a failed transfer fails
+        /// it. Subject to the same sweep delay and automatic extension as
+        /// [`sweep_unclaimed`]; a sweep that extends the campaign transfers
+        /// nothing.
+        ///
+        /// # Returns
+        /// The amount swept for each entry of `assets`, in order.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::ClaimPeriodActive`]: if the claim window or the sweep
+        ///   delay after it is still running.
+        /// - [`Error::TransferFailed`]: if any token transfer fails.
+        #[ink(message)]
+        pub fn sweep_all(&mut self, assets: Vec<AssetId>) -> Result<Vec<U256>> {
+            self.check_owner()?;
+            self.check_sweep_available()?;
+            if self.auto_extend()? {
+                return Ok(assets.iter().map(|_| U256::zero()).collect());
+            }
+
+            let to = if self.sweep_to_burn {
+                BURN_ADDRESS
+            } else {
+                self.env().caller()
+            };
+            let contract = self.env().address();
+
+            let mut swept = Vec::with_capacity(assets.len());
+            for id in assets {
+                let amount = if id == self.asset_id() {
+                    let amount = self.unreserved_balance();
+                    if !amount.is_zero() {
+                        self.transfer_out(to, amount)?;
+                    }
+                    amount
+                } else {
+                    let mut precompile: ink::contract_ref!(Erc20, DefaultEnvironment, Sol) =
+                        prefixed_address(PRECOMPILE_INDEX, id).into();
+
+                    let amount = precompile
+                        .balanceOf(contract)
+                        .saturating_sub(self.reserved_asset(id));
+                    if !amount.is_zero() {
+                        self.check_transfer(precompile.transfer(to, amount))?;
+                    }
+                    amount
+                };
+
+                if !amount.is_zero() {
+                    self.env().emit_event(Swept { to, amount });
+                }
+
+                swept.push(amount);
+            }
+
+            Ok(swept)
+        }
+
+        /// Sweep unclaimed tokens to `new_owner` and hand them the owner role.
+        ///
+        /// Combines a [`sweep_to`] and an ownership transfer so a handoff
+        /// cannot be left half done. Emits [`Swept`] and then
+        /// [`OwnershipTransferred`]. Subject to the same sweep delay and
+        /// automatic extension as [`sweep_to`]; if the campaign is extended
+        /// instead, ownership stays with the caller until a later handoff.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::ZeroAddress`]: if `new_owner` is the zero address.
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::ClaimPeriodActive`]: if the claim window or the sweep
+        ///   delay after it is still running.
+        /// - [`Error::BurnRequired`]: if the campaign sweeps to
+        ///   [`BURN_ADDRESS`].
+        /// - [`Error::NothingToSweep`]: if the contract's balance is zero.
+        #[ink(message)]
+        pub fn sweep_and_transfer_ownership(&mut self, new_owner: Address) -> Result<()> {
+            self.check_owner()?;
+            if new_owner == Address::zero() {
+                return Err(Error::ZeroAddress);
+            }
+            self.check_sweep_available()?;
+            if self.sweep_to_burn {
+                return Err(Error::BurnRequired);
+            }
+            if self.auto_extend()? {
+                return Ok(());
+            }
+
+            self.sweep_balance(new_owner)?;
+
+            let previous = self.owner;
+            self.owner = new_owner;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                new: new_owner,
+            });
+
+            Ok(())
+        }
+
+        /// Refund the caller's pro-rata share of unclaimed tokens.
+        ///
+        /// After the campaign ends, or at once if it has been cancelled, each
+        /// funder may withdraw
+        /// `remaining * funded_by(caller) / total_contributions`, where
+        /// `remaining` is the contract's current token balance less any pending
+        /// withdrawals owed to claimants. The caller's
+        /// contribution is zeroed, so a funder can only be refunded once.
+        ///
+        /// **Note:** Refunds share the same balance as [`sweep_unclaimed`];
+        /// once the owner sweeps, there is nothing left to refund.
+        ///
+        /// # Errors
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::ClaimPeriodActive`]: if the claim window is still open
+        ///   and the campaign has not been cancelled.
+        /// - [`Error::NothingToRefund`]: if the caller has no contribution.
+        /// - [`Error::Overflow`]: if the share computation overflows.
+        /// - [`Error::TransferFailed`]: if the token transfer fails.
+        #[ink(message)]
+        pub fn refund(&mut self) -> Result<()> {
+            self.check_initialized()?;
+            if !self.campaign_cancelled() {
+                self.check_campaign_ended()?;
+            }
+
+            let funder = self.env().caller();
+            let contribution = self.funded_by(funder);
+
+            if contribution.is_zero() {
+                return Err(Error::NothingToRefund);
+            }
+
+            let remaining = self.unreserved_balance();
+            let amount = remaining.checked_mul(contribution).ok_or(Error::Overflow)?
+                / self.total_contributions;
+
+            self.contributions.remove(funder);
+            self.total_contributions = self.total_contributions.saturating_sub(contribution);
+
+            if !amount.is_zero() {
+                self.transfer_out(funder, amount)?;
+            }
+
+            self.env().emit_event(Refunded { funder, amount });
+
+            Ok(())
+        }
+
+        /// Withdraw the caller's pending claim balance.
+        ///
+        /// Only used when the campaign was deployed with `pull_payments`;
+        /// claims then credit the amount here and this performs the actual
+        /// token transfer. Pending balances can still be withdrawn after the
+        /// campaign ends and are never swept or refunded.
+        ///
+        /// # Errors
+        /// - [`Error::NothingToWithdraw`]: if the caller has nothing pending.
+        /// - [`Error::TransferFailed`]: if the token transfer fails.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<()> {
+            let recipient = self.env().caller();
+            let amount = self.pending_withdrawal(recipient);
+
+            if amount.is_zero() {
+                return Err(Error::NothingToWithdraw);
+            }
+
+            self.pending_withdrawals.remove(recipient);
+            self.reserved = self.reserved.saturating_sub(amount);
+
+            self.transfer_out(self.payout_address(recipient), amount)?;
+
+            self.env().emit_event(Withdrawn { recipient, amount });
+
+            Ok(())
+        }
+
+        /// Retry the transfer of the caller's [`owed`] claim payout.
+        ///
+        /// The claim was verified when it was first submitted, so no proof is
+        /// needed. If the transfer fails again, the amount stays owed.
+        ///
+        /// # Errors
+        /// - [`Error::NothingOwed`]: if the caller is owed nothing.
+        /// - [`Error::TransferFailed`]: if the token transfer fails.
+        #[ink(message)]
+        pub fn retry_claim(&mut self) -> Result<()> {
+            let recipient = self.env().caller();
+            let amount = self.owed(recipient);
+
+            if amount.is_zero() {
+                return Err(Error::NothingOwed);
+            }
+
+            self.owed.remove(recipient);
+            self.reserved = self.reserved.saturating_sub(amount);
+
+            self.transfer_out(self.payout_address(recipient), amount)?;
+
+            self.env().emit_event(Withdrawn { recipient, amount });
+
+            Ok(())
+        }
+
+        /// Retry the transfer of the caller's [`owed_asset`] payout of asset
+        /// `id` from [`claim_multi_asset`].
+        ///
+        /// The claim was verified when it was first submitted, so no proof is
+        /// needed. On success the asset is marked paid in [`asset_receipt`]
+        /// and [`AssetClaimed`] is emitted; if the transfer fails again, the
+        /// amount stays owed.
+        ///
+        /// # Errors
+        /// - [`Error::NothingOwed`]: if the caller is owed none of `id`.
+        /// - [`Error::TransferFailed`]: if the token transfer fails.
+        #[ink(message)]
+        pub fn retry_asset_claim(&mut self, id: AssetId) -> Result<()> {
+            let recipient = self.env().caller();
+            let amount = self.owed_asset(recipient, id);
+
+            if amount.is_zero() {
+                return Err(Error::NothingOwed);
+            }
+
+            self.owed_assets.remove((recipient, id));
+            let reserved = self.reserved_asset(id).saturating_sub(amount);
+            self.reserved_assets.insert(id, &reserved);
+            if let Some(mut payouts) = self.asset_receipts.get(recipient) {
+                for payout in payouts.iter_mut().filter(|payout| payout.id == id) {
+                    payout.paid = true;
+                }
+                self.asset_receipts.insert(recipient, &payouts);
+            }
+
+            self.transfer_asset_out(id, self.payout_address(recipient), amount)?;
+
+            self.env().emit_event(AssetClaimed {
+                recipient,
+                id,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Claim tokens into an escrow that unlocks at `unlock_time`.
+        ///
+        /// The leaf commits to `unlock_time` as well, hashed as
+        /// `H(LEAF_ESCROW || recipient || value || unlock_time)` with the time
+        /// as 8 big-endian bytes, so claimants cannot pick their own lockup.
+        /// The payout is held by the contract, excluded from sweeps, until
+        /// [`withdraw_escrow`] releases it.
+        ///
+        /// # Errors
+        /// - The same errors as [`claim`], except [`Error::TransferFailed`].
+        #[ink(message)]
+        pub fn claim_to_escrow(
+            &mut self,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+            unlock_time: u64,
+        ) -> Result<()> {
+            self.sync_root()?;
+            let recipient = self.env().caller();
+
+            let leaf = self.escrow_leaf(recipient, value, unlock_time);
+            self.validate_leaf(recipient, leaf, value, &proof, index)?;
+
+            let amount = self.scale_amount(value)?;
+            self.record_claim(recipient, amount, index, &proof)?;
+            self.set_claimed_index(index);
+            self.count_claim();
+
+            let amount = self.account_payout(recipient, amount)?;
+            self.escrows.insert(recipient, &(amount, unlock_time));
+            self.reserved = self.reserved.saturating_add(amount);
+
+            self.env().emit_event(Escrowed {
+                recipient,
+                amount,
+                unlock_time,
+            });
+
+            Ok(())
+        }
+
+        /// Withdraw the caller's escrowed claim once it has unlocked.
+        ///
+        /// # Errors
+        /// - [`Error::NothingToWithdraw`]: if the caller has no escrow.
+        /// - [`Error::EscrowLocked`]: if the escrow's unlock time has not passed.
+        /// - [`Error::TransferFailed`]: if the token transfer fails.
+        #[ink(message)]
+        pub fn withdraw_escrow(&mut self) -> Result<()> {
+            let recipient = self.env().caller();
+            let (amount, unlock_time) = self
+                .escrows
+                .get(recipient)
+                .ok_or(Error::NothingToWithdraw)?;
+
+            if self.env().block_timestamp() < unlock_time {
+                return Err(Error::EscrowLocked);
+            }
+
+            self.escrows.remove(recipient);
+            self.reserved = self.reserved.saturating_sub(amount);
+
+            self.transfer_out(self.payout_address(recipient), amount)?;
+
+            self.env().emit_event(Withdrawn { recipient, amount });
+
+            Ok(())
+        }
+
+        /// Queue a change of `campaign_end_time` to `new_end_time`, applicable
+        /// after `end_time_timelock`.
+        ///
+        /// Gives claimers advance notice of deadline changes in either
+        /// direction. Replaces any change already queued.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::InvalidEndTime`]: if `new_end_time` is not after both the
+        ///   end of the timelock and `campaign_start_time`, or is more than
+        ///   [`MAX_DURATION`] away.
+        #[ink(message)]
+        pub fn queue_end_time_change(&mut self, new_end_time: u64) -> Result<()> {
+            self.check_owner()?;
+
+            let now = self.env().block_timestamp();
+            let effective_at = now.saturating_add(self.end_time_timelock);
+            if new_end_time <= effective_at
+                || new_end_time <= self.campaign_start_time
+                || new_end_time - now > MAX_DURATION
+            {
+                return Err(Error::InvalidEndTime);
+            }
+
+            self.pending_end_time = new_end_time;
+            self.end_time_effective_at = effective_at;
+
+            self.env().emit_event(EndTimeChangeQueued {
+                new_end_time,
+                effective_at,
+            });
+
+            Ok(())
+        }
+
+        /// Apply the end time queued by [`queue_end_time_change`].
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::InvalidEndTime`]: if no change is queued, or the queued
+        ///   end time has already passed.
+        /// - [`Error::TimelockNotElapsed`]: if the timelock is still running.
+        #[ink(message)]
+        pub fn apply_end_time_change(&mut self) -> Result<()> {
+            self.check_owner()?;
+
+            let now = self.env().block_timestamp();
+            let new_end_time = self.pending_end_time;
+            if new_end_time == 0 || new_end_time <= now {
+                return Err(Error::InvalidEndTime);
+            }
+            if now < self.end_time_effective_at {
+                return Err(Error::TimelockNotElapsed);
+            }
+
+            self.campaign_end_time = new_end_time;
+            self.pending_end_time = 0;
+            self.end_time_effective_at = 0;
+
+            self.env().emit_event(EndTimeChanged { new_end_time });
+
+            Ok(())
+        }
+
+        /// Close the claim window earlier.
+        ///
+        /// Takes effect immediately only if `end_time_timelock` is zero.
+        /// Otherwise the change is queued like with [`queue_end_time_change`],
+        /// so claimers get the same notice as for any other deadline change,
+        /// and must be applied with [`apply_end_time_change`].
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::InvalidEndTime`]: if `new_end_time` is not before the
+        ///   current end, is already in the past, or is not after
+        ///   `campaign_start_time`; or, with a timelock, is not after the end
+        ///   of the timelock.
+        #[ink(message)]
+        pub fn shorten_campaign(&mut self, new_end_time: u64) -> Result<()> {
+            self.check_owner()?;
+
+            let now = self.env().block_timestamp();
+            if new_end_time >= self.campaign_end_time
+                || new_end_time <= now
+                || new_end_time <= self.campaign_start_time
+            {
+                return Err(Error::InvalidEndTime);
+            }
+            if self.end_time_timelock > 0 {
+                return self.queue_end_time_change(new_end_time);
+            }
+
+            self.campaign_end_time = new_end_time;
+
+            self.env().emit_event(CampaignShortened { new_end_time });
+
+            Ok(())
+        }
+
+        /// Mark `addresses` as already claimed, for migrating a campaign.
+        ///
+        /// Lets recipients who claimed on a previous deployment be carried
+        /// over so they cannot claim again here. Only possible until the
+        /// first claim is paid, so it cannot be used to block claims mid-way.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::ClaimsAlreadyStarted`]: if any claim has been paid.
+        #[ink(message)]
+        pub fn mark_claimed(&mut self, addresses: Vec<Address>) -> Result<()> {
+            self.check_owner()?;
+            if !self.total_claimed.is_zero() {
+                return Err(Error::ClaimsAlreadyStarted);
+            }
+
+            for address in addresses.iter() {
+                self.claimed.insert(*address, &true);
+            }
+
+            self.env().emit_event(ClaimedSeeded {
+                count: addresses.len() as u32,
+            });
+
+            Ok(())
+        }
+
+        /// Correct a `decimals_factor` misconfigured at deployment.
+        ///
+        /// Only possible until the first payout, so amounts cannot change for
+        /// some recipients after others have been paid. Like [`mark_claimed`],
+        /// this goes by `total_claimed`, which also counts partial payouts
+        /// such as vested nonce entries.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::ClaimsAlreadyStarted`]: if anything has been claimed.
+        /// - [`Error::AmountCannotBeZero`]: if `factor` is zero.
+        #[ink(message)]
+        pub fn set_decimals_factor(&mut self, factor: U256) -> Result<()> {
+            self.check_owner()?;
+            if !self.total_claimed.is_zero() {
+                return Err(Error::ClaimsAlreadyStarted);
+            }
+            if factor.is_zero() {
+                return Err(Error::AmountCannotBeZero);
+            }
+
+            let old = self.decimals_factor;
+            self.decimals_factor = factor;
+
+            self.env()
+                .emit_event(DecimalsFactorUpdated { old, new: factor });
+
+            Ok(())
+        }
+
+        /// Pay `who`'s claims to `to` instead, or to `who` again if `to` is
+        /// the zero address.
+        ///
+        /// For smart-contract accounts that can prove eligibility but cannot
+        /// hold the asset themselves. Claims are still recorded against
+        /// `who`; only the token transfer is redirected.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        #[ink(message)]
+        pub fn set_payout_override(&mut self, who: Address, to: Address) -> Result<()> {
+            self.check_owner()?;
+
+            if to == Address::zero() {
+                self.payout_overrides.remove(who);
+            } else {
+                self.payout_overrides.insert(who, &to);
+            }
+
+            self.env().emit_event(PayoutOverrideUpdated { who, to });
+
+            Ok(())
+        }
+
+        /// Add `who` to or remove them from the allowlist.
+        ///
+        /// Only has an effect on claims if the campaign was deployed with
+        /// `require_allowlist`.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        #[ink(message)]
+        pub fn set_allowed(&mut self, who: Address, allowed: bool) -> Result<()> {
+            self.check_owner()?;
+
+            if allowed {
+                self.allowlist.insert(who, &true);
+            } else {
+                self.allowlist.remove(who);
+            }
+
+            self.env().emit_event(AllowlistUpdated { who, allowed });
+
+            Ok(())
+        }
+
+        /// Configure a campaign deployed with [`new_uninitialized`].
+        ///
+        /// Can only succeed once. Claims open immediately and close at
+        /// `campaign_end_time`.
+        ///
+        /// # Arguments
+        /// - `root`: Merkle root of the recipient tree.
+        /// - `campaign_end_time`: last block timestamp at which claims are accepted.
+        /// - `num_leaves`: number of leaves committed to by `root`.
+        /// - `asset`: address of the asset contract to distribute.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::AlreadyInitialized`]: if the campaign is already configured.
+        /// - [`Error::InvalidEndTime`]: if `campaign_end_time` is not in the
+        ///   future, or more than [`MAX_DURATION`] away.
+        /// - [`Error::InvalidRoot`]: if `root` is zero or `num_leaves` is zero.
+        /// - [`Error::ZeroAddress`]: if `asset` is the zero address.
+        #[ink(message)]
+        pub fn init(
+            &mut self,
+            root: [u8; 32],
+            campaign_end_time: u64,
+            num_leaves: u64,
+            asset: Address,
+        ) -> Result<()> {
+            self.check_owner()?;
+            if self.initialized {
+                return Err(Error::AlreadyInitialized);
+            }
+            let now = self.env().block_timestamp();
+            if campaign_end_time <= now || campaign_end_time - now > MAX_DURATION {
+                return Err(Error::InvalidEndTime);
+            }
+            if root == [0u8; 32] || num_leaves == 0 {
+                return Err(Error::InvalidRoot);
+            }
+            if asset == Address::zero() {
+                return Err(Error::ZeroAddress);
+            }
+
+            self.root = root;
+            self.campaign_start_time = 0;
+            self.campaign_end_time = campaign_end_time;
+            self.num_leaves = num_leaves;
+            self.asset_contract = AssetHubPrecompileRef::from_addr(asset);
+            self.initialized = true;
+            self.root_log.insert(0, &(root, now));
+
+            Ok(())
+        }
+
+        /// Replace the Merkle root, e.g. to add recipients mid-campaign.
+        ///
+        /// The previous root is kept for [`ROOT_GRACE_PERIOD`] so claims
+        /// prepared against it can still land via [`claim_against_root`].
+        /// Leaf indices keep their claimed status, so the new tree should
+        /// keep existing leaves at the same positions and only append new
+        /// ones, with `new_num_leaves` counting both.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is neither the owner nor the operator.
+        /// - [`Error::NotInitialized`]: if the campaign has not been
+        ///   configured with [`init`].
+        /// - [`Error::RootProviderActive`]: if the campaign reads its root from
+        ///   a `root_provider`, which would overwrite `new_root`.
+        /// - [`Error::InvalidRoot`]: if `new_root` is all zeros or
+        ///   `new_num_leaves` is zero.
+        #[ink(message)]
+        pub fn update_root(&mut self, new_root: [u8; 32], new_num_leaves: u64) -> Result<()> {
+            self.check_operator_or_owner()?;
+            self.check_initialized()?;
+            if self.root_provider != Address::zero() {
+                return Err(Error::RootProviderActive);
+            }
+            if new_root == [0u8; 32] || new_num_leaves == 0 {
+                return Err(Error::InvalidRoot);
+            }
+
+            self.replace_root(new_root);
+            self.num_leaves = new_num_leaves;
+
+            Ok(())
+        }
+
+        /// Appoint `operator`, or remove it by passing the zero address.
+        ///
+        /// The operator can [`pause`], [`unpause`] and [`update_root`], so a
+        /// hot key can run the campaign while the owner key stays cold for
+        /// sweeps and other treasury actions.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        #[ink(message)]
+        pub fn set_operator(&mut self, operator: Address) -> Result<()> {
+            self.check_owner()?;
+
+            let previous = self.operator;
+            self.operator = operator;
+
+            self.env().emit_event(OperatorUpdated {
+                previous,
+                new: operator,
+            });
+
+            Ok(())
+        }
+
+        /// Suspend all claim payouts until [`unpause`].
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is neither the owner nor the operator.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            self.set_paused(true)
+        }
+
+        /// Resume claim payouts after [`pause`].
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is neither the owner nor the operator.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            self.set_paused(false)
+        }
+
+        /// Open claims after [`disable_claims`].
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        #[ink(message)]
+        pub fn enable_claims(&mut self) -> Result<()> {
+            self.set_claims_enabled(true)
+        }
+
+        /// Reject claims until [`enable_claims`], e.g. while a campaign is
+        /// being prepared.
+        ///
+        /// Unlike [`pause`], which the operator can also trigger in an
+        /// emergency, this is an owner-only administrative state.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        #[ink(message)]
+        pub fn disable_claims(&mut self) -> Result<()> {
+            self.set_claims_enabled(false)
+        }
+
+        /// Close funding, fixing `total_funded` so [`claim_share`] can open.
+        ///
+        /// With a `fund_deadline`, this is also the solvency check: a campaign
+        /// funded with less than `expected_total` is cancelled instead, so
+        /// claims never open against a balance that cannot cover them and
+        /// funders can [`refund`] straight away. Once the deadline has passed
+        /// anyone may call this, so settling a campaign that missed it does
+        /// not depend on the owner. Emits [`FundingFinalized`] with the
+        /// outcome.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner and the
+        ///   `fund_deadline` has not passed.
+        #[ink(message)]
+        pub fn finalize_funding(&mut self) -> Result<()> {
+            if !self.fund_deadline_passed() {
+                self.check_owner()?;
+            }
+
+            let success = !self.underfunded();
+            self.funding_finalized = true;
+            if !success {
+                self.cancelled = true;
+            }
+
+            self.env().emit_event(FundingFinalized {
+                success,
+                total_funded: self.total_funded,
+            });
+
+            Ok(())
+        }
+
+        /// Change the claim fee to `fee_bps` basis points of each payout.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::FeeTooHigh`]: if `fee_bps` exceeds `max_fee_bps`.
+        #[ink(message)]
+        pub fn set_fee_bps(&mut self, fee_bps: u16) -> Result<()> {
+            self.check_owner()?;
+            if fee_bps > self.max_fee_bps {
+                return Err(Error::FeeTooHigh);
+            }
+
+            let old = self.fee_bps;
+            self.fee_bps = fee_bps;
+
+            self.env().emit_event(FeeUpdated { old, new: fee_bps });
+
+            Ok(())
+        }
+
+        /// Update the campaign metadata URI.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        #[ink(message)]
+        pub fn set_metadata_uri(&mut self, uri: String) -> Result<()> {
+            self.check_owner()?;
+
+            self.metadata_uri = uri.clone();
+
+            self.env().emit_event(MetadataUpdated { uri });
+
+            Ok(())
+        }
+
+        /// Point the campaign at a different asset contract.
+        ///
+        /// Recovery path for deploying against the wrong asset; only allowed
+        /// while the contract holds no deposits.
+        ///
+        /// # Errors
+        /// - [`Error::Unauthorized`]: if caller is not the owner.
+        /// - [`Error::AlreadyFunded`]: if [`fund`] has ever succeeded.
+        #[ink(message)]
+        pub fn set_asset_contract(&mut self, address: Address) -> Result<()> {
+            self.check_owner()?;
+            if self.funded {
+                return Err(Error::AlreadyFunded);
+            }
+
+            self.asset_contract = AssetHubPrecompileRef::from_addr(address);
+
+            self.env().emit_event(AssetContractUpdated {
+                asset_contract: address,
+            });
+
+            Ok(())
+        }
+
+        /// Get the token asset id of the asset contract.
+        #[ink(message)]
+        pub fn asset_id(&self) -> AssetId {
+            self.asset_contract.assetId()
+        }
+
+        /// Check that the asset contract responds to a trial `assetId` read.
+        ///
+        /// A plain account passed as `asset_contract_address` accepts every
+        /// call without running anything, so transfers would report nothing
+        /// and claims would fail in confusing ways. The constructor cannot
+        /// require code at the address, since the Asset Hub precompiles have
+        /// none, so deployments should call this before funding.
+        #[ink(message)]
+        pub fn validate_asset(&self) -> bool {
+            matches!(self.asset_contract.call().assetId().try_invoke(), Ok(Ok(_)))
+        }
+
+        /// Get the asset contract's balance of `who`.
+        ///
+        /// Lets integrators check a recipient's tokens through the airdrop
+        /// without looking up the asset address.
+        #[ink(message)]
+        pub fn asset_balance_of(&self, who: Address) -> U256 {
+            self.asset_contract.balanceOf(who)
+        }
+
+        /// Get the tokens the contract holds that are not owed to anyone yet.
+        ///
+        /// Excludes claims credited to [`pending_withdrawal`] but not yet
+        /// withdrawn, so this is what a sweep or refund would draw from.
+        #[ink(message)]
+        pub fn remaining_balance(&self) -> U256 {
+            self.unreserved_balance()
+        }
+
+        /// Check whether the contract can still pay every remaining claim.
+        ///
+        /// Compares [`remaining_balance`] with what `max_distribution`
+        /// still allows to be claimed. Integrators can poll this to warn
+        /// users before a campaign runs out of tokens. Always `false` for
+        /// uncapped campaigns, whose remaining allocations are unbounded.
+        #[ink(message)]
+        pub fn assert_solvent(&self) -> bool {
+            let outstanding = self.max_distribution.saturating_sub(self.total_claimed);
+
+            self.remaining_balance() >= outstanding
+        }
+
+        /// Get the Merkle root.
+        ///
+        /// With a `root_provider`, this is the root last cached by a claim;
+        /// the provider itself has the current one.
+        #[ink(message)]
+        pub fn root(&self) -> [u8; 32] {
+            self.root
+        }
+
+        /// Get the sequence number of the current root.
+        #[ink(message)]
+        pub fn root_index(&self) -> u32 {
+            self.root_index
+        }
+
+        /// Get the root numbered `root_index`, if it still accepts proofs.
+        ///
+        /// # Errors
+        /// - [`Error::RootExpired`]: if `root_index` is not the current root
+        ///   or a replaced root still within its grace period.
+        #[ink(message)]
+        pub fn root_at(&self, root_index: u32) -> Result<[u8; 32]> {
+            if root_index == self.root_index {
+                return Ok(self.root);
+            }
+
+            let age = self.root_index.checked_sub(root_index);
+            if !matches!(age, Some(age) if age <= ROOT_HISTORY_SIZE) {
+                return Err(Error::RootExpired);
+            }
+
+            let (root, replaced_at) = self
+                .recent_roots
+                .get(root_index % ROOT_HISTORY_SIZE)
+                .ok_or(Error::RootExpired)?;
+            if self.env().block_timestamp() > replaced_at.saturating_add(ROOT_GRACE_PERIOD) {
+                return Err(Error::RootExpired);
+            }
+
+            Ok(root)
+        }
+
+        /// Get the roots the campaign has used and when each was set, oldest
+        /// first.
+        ///
+        /// Starts with the root set at deployment or by [`init`], followed by
+        /// every [`update_root`] and every new root read from `root_provider`.
+        /// Only the last [`ROOT_LOG_SIZE`] are kept; together with
+        /// [`RootUpdated`] events this shows how the committed set evolved.
+        #[ink(message)]
+        pub fn root_history(&self) -> Vec<([u8; 32], u64)> {
+            let oldest = self
+                .root_index
+                .saturating_sub(ROOT_LOG_SIZE)
+                .saturating_add(1);
+
+            (oldest..=self.root_index)
+                .filter_map(|root_index| self.root_log.get(root_index % ROOT_LOG_SIZE))
+                .collect()
+        }
+
+        /// Get the contract owner.
+        #[ink(message)]
+        pub fn owner(&self) -> Address {
+            self.owner
+        }
+
+        /// Get the operator, or the zero address if none is set.
+        #[ink(message)]
+        pub fn operator(&self) -> Address {
+            self.operator
+        }
+
+        /// Check if claims are paused.
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Check if the owner has claims enabled.
+        #[ink(message)]
+        pub fn claims_enabled(&self) -> bool {
+            self.claims_enabled
+        }
+
+        /// Get the block timestamp after which claims are rejected.
+        #[ink(message)]
+        pub fn campaign_end_time(&self) -> u64 {
+            self.campaign_end_time
+        }
+
+        /// Get the queued end time change as `(new_end_time, effective_at)`,
+        /// or zeros if none is queued.
+        #[ink(message)]
+        pub fn pending_end_time(&self) -> (u64, u64) {
+            (self.pending_end_time, self.end_time_effective_at)
+        }
+
+        /// Get the block timestamp after which the owner may sweep.
+        ///
+        /// Counts from the end of the grace period, so late claims are never
+        /// swept out from under their recipients.
+        #[ink(message)]
+        pub fn sweep_available_at(&self) -> u64 {
+            self.claims_close_at().saturating_add(self.sweep_delay)
+        }
+
+        /// Get the number of leaves committed to by the Merkle root.
+        #[ink(message)]
+        pub fn num_leaves(&self) -> u64 {
+            self.num_leaves
+        }
+
+        /// Count the claims made between `from` and `to`, both inclusive.
+        ///
+        /// Backed by a ring buffer of the last [`CLAIM_LOG_SIZE`] claim
+        /// timestamps, which costs one storage write per claim but keeps
+        /// storage bounded; ranges reaching further back only count the
+        /// claims still logged. Reversed claims stay counted.
+        #[ink(message)]
+        pub fn claims_in_range(&self, from: u64, to: u64) -> u64 {
+            if from > to {
+                return 0;
+            }
+
+            let first = self.claim_log_partition(|timestamp| timestamp < from);
+            let end = self.claim_log_partition(|timestamp| timestamp <= to);
+
+            end - first
+        }
+
+        /// Get how many of the [`num_leaves`] leaves have been claimed.
+        #[ink(message)]
+        pub fn claimed_count(&self) -> u64 {
+            self.claimed_count
+        }
+
+        /// Get the off-chain metadata URI describing the campaign.
+        #[ink(message)]
+        pub fn metadata_uri(&self) -> String {
+            self.metadata_uri.clone()
+        }
+
+        /// Get the token amount paid per unit by [`claim_units`].
+        #[ink(message)]
+        pub fn unit_value(&self) -> U256 {
+            self.unit_value
+        }
+
+        /// Get the multiplier applied to committed amounts on payout.
+        #[ink(message)]
+        pub fn decimals_factor(&self) -> U256 {
+            self.decimals_factor
+        }
+
+        /// Get the most entries a single [`claim_batch`] call may hold.
+        #[ink(message)]
+        pub fn max_batch(&self) -> u32 {
+            self.max_batch
+        }
+
+        /// Get the hashing algorithm the Merkle tree is expected to use.
+        #[ink(message)]
+        pub fn hash_algo(&self) -> HashAlgo {
+            self.hash_algo
+        }
+
+        /// Get how much of the claim window has elapsed, in basis points.
+        ///
+        /// `0` before `campaign_start_time`, rising linearly to [`MAX_BPS`] at
+        /// `campaign_end_time` and clamped there afterwards.
+        #[ink(message)]
+        pub fn vested_bps(&self) -> u16 {
+            let now = self.env().block_timestamp();
+            if now <= self.campaign_start_time {
+                return 0;
+            }
+            if now >= self.campaign_end_time {
+                return MAX_BPS;
+            }
+
+            let elapsed = u128::from(now - self.campaign_start_time);
+            let duration = u128::from(self.campaign_end_time - self.campaign_start_time);
+
+            (elapsed * u128::from(MAX_BPS) / duration) as u16
+        }
+
+        /// Check whether claims are currently accepted.
+        ///
+        /// Combines every condition `claim` enforces on the campaign itself,
+        /// i.e. the claim window, [`pause`], [`disable_claims`] and
+        /// cancellation, so front-ends can enable or disable a claim button
+        /// with one call. Same as [`state`] being [`CampaignState::Active`].
+        #[ink(message)]
+        pub fn is_active(&self) -> bool {
+            self.state() == CampaignState::Active
+        }
+
+        /// Get the campaign's current status as a single value.
+        ///
+        /// Cancellation takes precedence, then the claim window, then
+        /// [`pause`] and [`disable_claims`]; a paused campaign whose window
+        /// has closed reports [`CampaignState::Ended`].
+        #[ink(message)]
+        pub fn state(&self) -> CampaignState {
+            if self.campaign_cancelled() {
+                return CampaignState::Cancelled;
+            }
+
+            match self.check_campaign_ongoing() {
+                Err(Error::ClaimPeriodNotStarted | Error::NotInitialized) => {
+                    CampaignState::NotStarted
+                }
+                Err(_) => CampaignState::Ended,
+                Ok(()) if self.paused || !self.claims_enabled => CampaignState::Paused,
+                Ok(()) => CampaignState::Active,
+            }
+        }
+
+        /// Check whether `timestamp` falls within the claim window.
+        ///
+        /// Applies the same start and end checks as [`claim`] to the given
+        /// time instead of the current block's, for building claim schedules.
+        #[ink(message)]
+        pub fn is_claimable_at(&self, timestamp: u64) -> bool {
+            self.check_window_at(timestamp).is_ok()
+        }
+
+        /// Get the claim window as `(start, end)` block timestamps.
+        #[ink(message)]
+        pub fn claim_window(&self) -> (u64, u64) {
+            (self.campaign_start_time, self.campaign_end_time)
+        }
+
+        /// Get the `keccak256` of the concatenated proof `who` claimed with.
+        ///
+        /// Lets post-mortems spot two different proofs accepted for the same
+        /// leaf, which would point to a malformed tree. All zero bytes if `who`
+        /// has not made a per-address claim.
+        #[ink(message)]
+        pub fn proof_digest(&self, who: Address) -> [u8; 32] {
+            self.proof_digests.get(who).unwrap_or_default()
+        }
+
+        /// Check if `who` is on the allowlist.
+        #[ink(message)]
+        pub fn is_allowed(&self, who: Address) -> bool {
+            self.allowlist.get(who).unwrap_or(false)
+        }
+
+        /// Check if a recipient has already claimed.
+        #[ink(message)]
+        pub fn is_claimed(&self, recipient: Address) -> bool {
+            self.claimed.get(recipient).unwrap_or(false)
+        }
+
+        /// Get the total amount paid out to `recipient` by claims, net of the
+        /// claim fee and any late penalty.
+        ///
+        /// Unlike [`is_claimed`], this sums every entry the recipient claimed,
+        /// including [`claim_with_nonce`] entries.
+        #[ink(message)]
+        pub fn claimed_amount(&self, recipient: Address) -> U256 {
+            self.claimed_amounts.get(recipient).unwrap_or_default()
+        }
+
+        /// Get the receipt of `who`'s claim from the main tree.
+        ///
+        /// # Errors
+        /// - [`Error::NotClaimed`]: if `who` has not claimed, or the claim
+        ///   was reversed.
+        #[ink(message)]
+        pub fn claim_receipt(&self, who: Address) -> Result<ClaimReceipt> {
+            self.receipts.get(who).ok_or(Error::NotClaimed)
+        }
+
+        /// Get the receipt of `who`'s claim from the shard tree `tree_id`.
+        ///
+        /// # Errors
+        /// - [`Error::NotClaimed`]: if `who` has not claimed from that shard.
+        #[ink(message)]
+        pub fn tree_claim_receipt(&self, tree_id: u32, who: Address) -> Result<ClaimReceipt> {
+            self.tree_receipts
+                .get((tree_id, who))
+                .ok_or(Error::NotClaimed)
+        }
+
+        /// Get the per-asset outcome of `who`'s [`claim_multi_asset`], in the
+        /// order of the claimed leaf.
+        ///
+        /// # Errors
+        /// - [`Error::NotClaimed`]: if `who` has not made a multi-asset claim.
+        #[ink(message)]
+        pub fn asset_receipt(&self, who: Address) -> Result<Vec<AssetPayout>> {
+            self.asset_receipts.get(who).ok_or(Error::NotClaimed)
+        }
+
+        /// Check claim status for many recipients in one call.
+        ///
+        /// Returns one entry per input address, in the same order.
+        #[ink(message)]
+        pub fn are_claimed(&self, recipients: Vec<Address>) -> Vec<bool> {
+            recipients
+                .into_iter()
+                .map(|recipient| self.is_claimed(recipient))
+                .collect()
+        }
+
+        /// Check if `recipient` has claimed the entry with `nonce`.
+        #[ink(message)]
+        pub fn is_claimed_nonce(&self, recipient: Address, nonce: u64) -> bool {
+            self.claimed_nonces.get((recipient, nonce)).unwrap_or(false)
+        }
+
+        /// Check claim status of several nonce entries of `recipient`.
+        ///
+        /// Returns one entry per input nonce, in the same order.
+        #[ink(message)]
+        pub fn are_claimed_nonces(&self, recipient: Address, nonces: Vec<u64>) -> Vec<bool> {
+            nonces
+                .into_iter()
+                .map(|nonce| self.is_claimed_nonce(recipient, nonce))
+                .collect()
+        }
+
+        /// Check if `recipient` has claimed from the shard tree `tree_id`.
+        #[ink(message)]
+        pub fn is_claimed_in_tree(&self, tree_id: u32, recipient: Address) -> bool {
+            self.tree_claimed.get((tree_id, recipient)).unwrap_or(false)
+        }
+
+        /// Get the root of the shard tree `tree_id`.
+        ///
+        /// # Errors
+        /// - [`Error::UnknownTree`]: if there is no shard with `tree_id`.
+        #[ink(message)]
+        pub fn tree_root(&self, tree_id: u32) -> Result<[u8; 32]> {
+            self.shard(tree_id).map(|(root, _)| root)
+        }
+
+        /// Get the committed amount of `recipient`'s entry `nonce` already
+        /// paid by [`claim_all_vested`].
+        #[ink(message)]
+        pub fn vested_claimed(&self, recipient: Address, nonce: u64) -> U256 {
+            self.vested_claimed
+                .get((recipient, nonce))
+                .unwrap_or_default()
+        }
+
+        /// Check if the leaf at `index` has already been claimed.
+        #[ink(message)]
+        pub fn is_claimed_index(&self, index: u64) -> bool {
+            let word = self.claimed_bitmap.get(index / 256).unwrap_or_default();
+            word.bit((index % 256) as usize)
+        }
+
+        /// Explain why a [`claim`] of `value` by `recipient` would be rejected.
+        ///
+        /// Runs the same checks as `claim` in the same order without writing
+        /// to storage, including the payout checks made once the leaf is
+        /// proven, and splits proof failures into a proof that is shorter
+        /// than the tree is deep and one that simply hashes to another root.
+        #[ink(message)]
+        pub fn why_invalid(
+            &self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> RejectionReason {
+            let checked = self
+                .validate_claim(recipient, value, &proof, index)
+                .and_then(|()| self.check_payout(recipient));
+
+            match checked {
+                Ok(()) => RejectionReason::Valid,
+                Err(Error::ClaimPeriodNotStarted | Error::ClaimPeriodOver) => {
+                    RejectionReason::OutsideWindow
+                }
+                Err(Error::AlreadyClaimed) => RejectionReason::AlreadyClaimed,
+                Err(Error::IndexOutOfRange) => RejectionReason::IndexOutOfRange,
+                Err(Error::NotAllowed) => RejectionReason::NotAllowed,
+                Err(Error::AmountCannotBeZero) => RejectionReason::ZeroAmount,
+                Err(Error::Paused) => RejectionReason::Paused,
+                Err(Error::ClaimsDisabled) => RejectionReason::ClaimsDisabled,
+                Err(Error::CampaignCancelled) => RejectionReason::Cancelled,
+                Err(Error::Cooldown) => RejectionReason::Cooldown,
+                Err(Error::RootProviderCallFailed) => RejectionReason::RootUnavailable,
+                Err(_) if (proof.len() as u64) < u64::from(self.tree_depth()) => {
+                    RejectionReason::ProofTooShort
+                }
+                Err(_) => RejectionReason::RootMismatch,
+            }
+        }
+
+        /// Preview the amount a [`claim`] by `recipient` would pay out.
+        ///
+        /// Runs every check `claim` runs, in the same order, and returns the
+        /// scaled payout net of the claim fee without transferring or writing anything, so
+        /// relayers can dry-run it before submitting.
+        ///
+        /// # Errors
+        /// - The same errors as [`claim`], except [`Error::TransferFailed`].
+        #[ink(message)]
+        pub fn preview_claim(
+            &self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> Result<U256> {
+            self.validate_claim(recipient, value, &proof, index)?;
+            let amount = self.scale_amount(value)?;
+            self.check_payout(recipient)?;
+            self.total_claimed_after(amount)?;
+
+            self.net_payout(amount)
+        }
+
+        /// Get the proof length [`claim`] expects for a tree of `leaf_count`
+        /// leaves, i.e. `ceil(log2(leaf_count))`.
+        ///
+        /// Lets tooling check that generated proofs have the right length
+        /// before submitting them; for this campaign's tree, pass `num_leaves`.
+        #[ink(message)]
+        pub fn required_depth(&self, leaf_count: u64) -> u8 {
+            // At most `u64::BITS`, so always fits.
+            depth_for(leaf_count) as u8
+        }
+
+        /// Check whether a [`claim`] by `recipient` would currently succeed.
+        ///
+        /// `true` only if the proof verifies, neither the recipient nor the
+        /// leaf has claimed, the window is open, and every other check
+        /// [`preview_claim`] runs passes. Use `preview_claim` to learn why a
+        /// claim would fail.
+        #[ink(message)]
+        pub fn can_claim(
+            &self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> bool {
+            self.preview_claim(recipient, value, proof, index).is_ok()
+        }
+
+        /// Compute the leaf hash [`claim`] checks for `(recipient, value)`.
+        ///
+        /// Uses the configured [`HashAlgo`] over the 20-byte address followed
+        /// by `value` as 32 big-endian bytes. Tree generators can dry-run this
+        /// to cross-check their own leaf encoding.
+        #[ink(message)]
+        pub fn leaf_of(&self, recipient: Address, value: U256) -> [u8; 32] {
+            self.leaf(recipient, value)
+        }
+
+        /// Compute the parent node of `left` and `right`, i.e.
+        /// `H(left || right)` with the configured [`HashAlgo`].
+        ///
+        /// The companion of [`leaf_of`] for checking the inner levels of an
+        /// off-chain tree byte for byte.
+        #[ink(message)]
+        pub fn node_hash(&self, left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+            hash(self.hash_algo, &left, &right)
+        }
+
+        /// Check `proof` for `(recipient, value)` as a sorted-pair proof.
+        ///
+        /// For tree generators that sort each pair instead of tracking leaf
+        /// positions, like OpenZeppelin's `MerkleProof`, to confirm their
+        /// output under the configured [`HashAlgo`]. Checks against the same
+        /// root as [`claim`], read from `root_provider` if set, and is `false`
+        /// if that root cannot be read. Equal siblings hash as `a || b`.
+        #[ink(message)]
+        pub fn verify_sorted_proof(
+            &self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+        ) -> bool {
+            let Ok(root) = self.active_root() else {
+                return false;
+            };
+
+            verify_proof_sorted(self.hash_algo, self.leaf(recipient, value), &proof, root)
+        }
+
+        /// Recompute `proof` for `(recipient, value)` at `index` step by step.
+        ///
+        /// Only built with the `trace` feature, for diagnosing rejected
+        /// claims: dry-run it on a node with the arguments of the failed
+        /// [`claim`] to see the leaf, every intermediate node and the root
+        /// it was compared with. Checks against the same root as `claim`.
+        #[cfg(feature = "trace")]
+        #[ink(message)]
+        pub fn trace_proof(
+            &self,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) -> ProofTrace {
+            let leaf = self.leaf(recipient, value);
+            let root = self.active_root().unwrap_or(self.root);
+            let proof = if self.zero_hashes.is_empty() {
+                proof
+            } else {
+                self.fill_zero_siblings(&proof).unwrap_or(proof)
+            };
+
+            let mut computed = leaf;
+            let mut position = index;
+            let mut steps = Vec::with_capacity(proof.len());
+            for sibling in &proof {
+                computed = if position % 2 == 0 {
+                    hash(self.hash_algo, &computed, sibling)
+                } else {
+                    hash(self.hash_algo, sibling, &computed)
+                };
+                position /= 2;
+                steps.push(computed);
+            }
+
+            ProofTrace {
+                leaf,
+                steps,
+                root,
+                verified: verify_proof(self.hash_algo, leaf, &proof, index, root),
+            }
+        }
+
+        /// Get the most the contract will ever pay out in claims.
+        #[ink(message)]
+        pub fn max_distribution(&self) -> U256 {
+            self.max_distribution
+        }
+
+        /// Get the sum of all amounts claimed so far.
+        ///
+        /// Counts each claim in full, before the claim fee and any late
+        /// penalty, as it is limited by `max_distribution`; [`claimed_amount`]
+        /// and claim receipts hold what recipients were paid instead.
+        #[ink(message)]
+        pub fn total_claimed(&self) -> U256 {
+            self.total_claimed
+        }
+
+        /// Get the sum of every deposit made through [`fund`].
+        ///
+        /// Unlike the contract's balance this never decreases, so
+        /// `total_funded - total_claimed - remaining_balance` should account
+        /// for what was swept or refunded.
+        #[ink(message)]
+        pub fn total_funded(&self) -> U256 {
+            self.total_funded
+        }
+
+        /// Get the earliest block timestamp at which `who` can be paid again.
+        ///
+        /// `0` if `who` has never been paid.
+        #[ink(message)]
+        pub fn next_claim_available(&self, who: Address) -> u64 {
+            self.last_payout_at
+                .get(who)
+                .map_or(0, |last| last.saturating_add(self.claim_cooldown))
+        }
+
+        /// Get where `who`'s claims are paid instead, or the zero address if
+        /// they are paid to `who`.
+        #[ink(message)]
+        pub fn payout_override(&self, who: Address) -> Address {
+            self.payout_overrides.get(who).unwrap_or_default()
+        }
+
+        /// Get the claimed amount `who` can still collect with [`withdraw`].
+        #[ink(message)]
+        pub fn pending_withdrawal(&self, who: Address) -> U256 {
+            self.pending_withdrawals.get(who).unwrap_or_default()
+        }
+
+        /// Get the verified claim payout `who` can still collect with
+        /// [`retry_claim`].
+        #[ink(message)]
+        pub fn owed(&self, who: Address) -> U256 {
+            self.owed.get(who).unwrap_or_default()
+        }
+
+        /// Get the amount of asset `id` that `who` can still collect with
+        /// [`retry_asset_claim`].
+        #[ink(message)]
+        pub fn owed_asset(&self, who: Address, id: AssetId) -> U256 {
+            self.owed_assets.get((who, id)).unwrap_or_default()
+        }
+
+        /// Get the amount of asset `id` held back from [`sweep_all`] for
+        /// [`retry_asset_claim`].
+        #[ink(message)]
+        pub fn reserved_asset(&self, id: AssetId) -> U256 {
+            self.reserved_assets.get(id).unwrap_or_default()
+        }
+
+        /// Get the total amount deposited by `funder` across all `fund` calls.
+        #[ink(message)]
+        pub fn funded_by(&self, funder: Address) -> U256 {
+            self.contributions.get(funder).unwrap_or_default()
+        }
+
+        /// Internal: run every check a claim by `recipient` must pass.
+        ///
+        /// Does not modify state, so it can back read-only paths as well.
+        fn validate_claim(
+            &self,
+            recipient: Address,
+            value: U256,
+            proof: &[[u8; 32]],
+            index: u64,
+        ) -> Result<()> {
+            self.validate_leaf(recipient, self.leaf(recipient, value), value, proof, index)
+        }
+
+        /// Internal: the `(recipient, value)` leaf hash `claim` verifies.
+        ///
+        /// `value` is committed in full, so the caller-supplied amount is
+        /// only ever accepted if it is exactly the allocation in the tree.
+        fn leaf(&self, recipient: Address, value: U256) -> [u8; 32] {
+            hash(self.hash_algo, recipient.as_bytes(), &value.to_big_endian())
+        }
+
+        /// Internal: the leaf hash `H(tag || recipient || data)` of kind `tag`.
+        fn tagged_leaf(&self, tag: u8, recipient: Address, data: &[u8]) -> [u8; 32] {
+            let mut left = [0u8; 21];
+            left[0] = tag;
+            left[1..].copy_from_slice(recipient.as_bytes());
+
+            hash(self.hash_algo, &left, data)
+        }
+
+        /// Internal: the `(recipient, assets)` leaf hash `claim_multi_asset` verifies.
+        fn multi_asset_leaf(&self, recipient: Address, assets: &[AssetAmount]) -> [u8; 32] {
+            let mut right = Vec::with_capacity(assets.len() * 36);
+            for asset in assets {
+                right.extend_from_slice(&asset.id.to_be_bytes());
+                right.extend_from_slice(&asset.amount.to_big_endian());
+            }
+
+            self.tagged_leaf(LEAF_MULTI_ASSET, recipient, &right)
+        }
+
+        /// Internal: the `(recipient, value, unlock_time)` leaf hash
+        /// `claim_to_escrow` verifies.
+        fn escrow_leaf(&self, recipient: Address, value: U256, unlock_time: u64) -> [u8; 32] {
+            let mut right = [0u8; 40];
+            right[..32].copy_from_slice(&value.to_big_endian());
+            right[32..].copy_from_slice(&unlock_time.to_be_bytes());
+
+            self.tagged_leaf(LEAF_ESCROW, recipient, &right)
+        }
+
+        /// Internal: the `(recipient, nonce, value)` leaf hash
+        /// `claim_with_nonce` verifies.
+        fn nonce_leaf(&self, recipient: Address, nonce: u64, value: U256) -> [u8; 32] {
+            let mut right = [0u8; 40];
+            right[..8].copy_from_slice(&nonce.to_be_bytes());
+            right[8..].copy_from_slice(&value.to_big_endian());
+
+            self.tagged_leaf(LEAF_NONCE, recipient, &right)
+        }
+
+        /// Internal: check the campaign, claim status, and proof of `leaf` for
+        /// `recipient`, whatever the leaf commits to besides the address.
+        ///
+        /// `value` is the amount the leaf commits to, for the zero check in
+        /// [`check_proof`].
+        fn validate_leaf(
+            &self,
+            recipient: Address,
+            leaf: [u8; 32],
+            value: U256,
+            proof: &[[u8; 32]],
+            index: u64,
+        ) -> Result<()> {
+            self.validate_leaf_under(self.active_root()?, recipient, leaf, value, proof, index)
+        }
+
+        /// Internal: [`validate_leaf`] against `root` instead of the current root.
+        fn validate_leaf_under(
+            &self,
+            root: [u8; 32],
+            recipient: Address,
+            leaf: [u8; 32],
+            value: U256,
+            proof: &[[u8; 32]],
+            index: u64,
+        ) -> Result<()> {
+            self.check_campaign_ongoing()?;
+            self.check_allowed(recipient)?;
+            self.check_index(index)?;
+
+            let already_claimed = self.is_claimed(recipient) || self.is_claimed_index(index);
+
+            if already_claimed {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            self.check_proof(root, self.num_leaves, leaf, value, proof, index)
+        }
+
+        /// Internal: check the campaign, `(recipient, nonce)` claim status, and
+        /// proof of a nonce leaf. Leaves the address-level `claimed` flag alone,
+        /// so one address may hold several nonce entries.
+        fn validate_nonce_leaf(
+            &self,
+            recipient: Address,
+            nonce: u64,
+            leaf: [u8; 32],
+            value: U256,
+            proof: &[[u8; 32]],
+            index: u64,
+        ) -> Result<()> {
+            self.check_campaign_ongoing()?;
+            self.check_allowed(recipient)?;
+            self.check_index(index)?;
+
+            let already_claimed =
+                self.is_claimed_nonce(recipient, nonce) || self.is_claimed_index(index);
+
+            if already_claimed {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            self.check_proof(
+                self.active_root()?,
+                self.num_leaves,
+                leaf,
+                value,
+                proof,
+                index,
+            )
+        }
+
+        /// Internal: the root claims are checked against.
+        ///
+        /// With a `root_provider`, reads it from the provider unless it was
+        /// already cached by [`sync_root`] in this block.
+        fn active_root(&self) -> Result<[u8; 32]> {
+            if self.root_provider == Address::zero()
+                || self.root_synced_at == Some(self.env().block_number())
+            {
+                return Ok(self.root);
+            }
+
+            self.fetch_root()
+        }
+
+        /// Internal: cache the provider's root for the rest of the block, so
+        /// several proofs checked in one block cost one cross-contract call.
+        fn sync_root(&mut self) -> Result<()> {
+            let block = self.env().block_number();
+            if self.root_provider == Address::zero() || self.root_synced_at == Some(block) {
+                return Ok(());
+            }
+
+            let root = self.fetch_root()?;
+            if root != self.root {
+                self.replace_root(root);
+            }
+            self.root_synced_at = Some(block);
+
+            Ok(())
+        }
+
+        /// Internal: make `new_root` the current root under the next
+        /// `root_index`, keeping the previous one for `claim_against_root` and
+        /// logging the new one for `root_history`.
+        fn replace_root(&mut self, new_root: [u8; 32]) {
+            let previous_root = self.root;
+            let now = self.env().block_timestamp();
+            self.recent_roots
+                .insert(self.root_index % ROOT_HISTORY_SIZE, &(previous_root, now));
+            self.root_index = self.root_index.saturating_add(1);
+            self.root = new_root;
+
+            self.root_log
+                .insert(self.root_index % ROOT_LOG_SIZE, &(new_root, now));
+
+            self.env().emit_event(RootUpdated {
+                previous_root,
+                new_root,
+                root_index: self.root_index,
+            });
+        }
+
+        /// Internal: read the current root from `root_provider`.
+        fn fetch_root(&self) -> Result<[u8; 32]> {
+            let provider: ink::contract_ref!(RootProvider) =
+                FromAddr::from_addr(self.root_provider);
+
+            match provider.call().root().try_invoke() {
+                Ok(Ok(root)) => Ok(root),
+                _ => Err(Error::RootProviderCallFailed),
+            }
+        }
+
+        /// Internal: number of levels above the leaves, i.e. the proof length
+        /// for a tree of `num_leaves` with odd nodes paired with themselves.
+        fn tree_depth(&self) -> u32 {
+            depth_for(self.num_leaves)
+        }
+
+        /// Internal: the root and leaf count of the shard tree `tree_id`.
+        fn shard(&self, tree_id: u32) -> Result<([u8; 32], u64)> {
+            self.shard_roots
+                .get(tree_id as usize)
+                .copied()
+                .ok_or(Error::UnknownTree)
+        }
+
+        /// Internal: ensure `recipient` is allowlisted, if the campaign requires it.
+        fn check_allowed(&self, recipient: Address) -> Result<()> {
+            if self.require_allowlist && !self.is_allowed(recipient) {
+                return Err(Error::NotAllowed);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: ensure `index` addresses one of the tree's `num_leaves`.
+        fn check_index(&self, index: u64) -> Result<()> {
+            if index >= self.num_leaves {
+                return Err(Error::IndexOutOfRange);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: ensure `proof` places `leaf`, committing to `value`, at
+        /// `index` under `root`, a tree of `num_leaves`.
+        ///
+        /// An empty proof means the leaf must equal the root, which is only a
+        /// valid tree when `num_leaves` is one; otherwise it is rejected, so a
+        /// root mistakenly set to some leaf cannot be claimed without a proof.
+        /// A proven leaf committing to zero is rejected too, since claiming it
+        /// would only mark the recipient claimed.
+        fn check_proof(
+            &self,
+            root: [u8; 32],
+            num_leaves: u64,
+            leaf: [u8; 32],
+            value: U256,
+            proof: &[[u8; 32]],
+            index: u64,
+        ) -> Result<()> {
+            if proof.is_empty() && num_leaves > 1 {
+                return Err(Error::InvalidProof);
+            }
+
+            let filled;
+            let proof = if self.zero_hashes.is_empty() {
+                proof
+            } else {
+                filled = self.fill_zero_siblings(proof)?;
+                &filled[..]
+            };
+
+            let verified = verify_proof(self.hash_algo, leaf, proof, index, root);
+
+            if !verified {
+                return Err(Error::InvalidProof);
+            }
+            if value.is_zero() {
+                return Err(Error::AmountCannotBeZero);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: replace each [`ZERO_SIBLING`] in `proof` with the empty
+        /// subtree root of its level.
+        ///
+        /// Fails with [`Error::InvalidProof`] for a sentinel above the levels
+        /// `zero_hashes` covers.
+        fn fill_zero_siblings(&self, proof: &[[u8; 32]]) -> Result<Vec<[u8; 32]>> {
+            proof
+                .iter()
+                .enumerate()
+                .map(|(level, sibling)| {
+                    if *sibling != ZERO_SIBLING {
+                        return Ok(*sibling);
+                    }
+
+                    self.zero_hashes
+                        .get(level)
+                        .copied()
+                        .ok_or(Error::InvalidProof)
+                })
+                .collect()
+        }
+
+        /// Internal: record a validated claim and pay it out to `recipient`.
+        fn settle_claim(
+            &mut self,
+            recipient: Address,
+            value: U256,
+            index: u64,
+            proof: &[[u8; 32]],
+        ) -> Result<()> {
+            self.record_claim(recipient, value, index, proof)?;
+            self.pay_claim(recipient, value, index)
+        }
+
+        /// Internal: write the per-recipient records of a validated claim of
+        /// `value`.
+        ///
+        /// The receipt holds what the recipient is paid, i.e. `value` net of
+        /// the claim fee and any late penalty, while `total_claimed` counts
+        /// `value` in full.
+        fn record_claim(
+            &mut self,
+            recipient: Address,
+            value: U256,
+            index: u64,
+            proof: &[[u8; 32]],
+        ) -> Result<()> {
+            self.claimed.insert(recipient, &true);
+            self.proof_digests
+                .insert(recipient, &hash_with::<Keccak256>(&proof.concat()));
+            self.claimed_at
+                .insert(recipient, &self.env().block_timestamp());
+            self.claimed_index.insert(recipient, &index);
+            self.receipts
+                .insert(recipient, &self.receipt(value, index)?);
+
+            Ok(())
+        }
+
+        /// Internal: the receipt of a claim of `value` from leaf `index` made
+        /// in this block.
+        fn receipt(&self, value: U256, index: u64) -> Result<ClaimReceipt> {
+            let amount = self.net_payout(value)?;
+
+            Ok(ClaimReceipt {
+                amount,
+                timestamp: self.env().block_timestamp(),
+                index,
+                withheld: value - amount,
+            })
+        }
+
+        /// Internal: mark the leaf at `index` claimed and pay `value` to
+        /// `recipient`.
+        fn pay_claim(&mut self, recipient: Address, value: U256, index: u64) -> Result<()> {
+            self.set_claimed_index(index);
+            self.count_claim();
+            self.pay_out(recipient, value)
+        }
+
+        /// Internal: account for and transfer a claim payout of `value`.
+        fn pay_out(&mut self, recipient: Address, value: U256) -> Result<()> {
+            let value = self.account_payout(recipient, value)?;
+            self.deliver(recipient, value)?;
+
+            let remaining_after = if self.emit_remaining_balance {
+                self.asset_contract.balanceOf(self.env().address())
+            } else {
+                U256::zero()
+            };
+
+            self.env().emit_event(Claimed {
+                recipient,
+                value,
+                remaining_after,
+            });
+
+            Ok(())
+        }
+
+        /// Internal: hand `value` to `recipient`, crediting it for
+        /// `withdraw` under pull payments and otherwise transferring
+        /// it, keeping it for `retry_claim` if the asset refuses.
+        fn deliver(&mut self, recipient: Address, value: U256) -> Result<()> {
+            if self.pull_payments {
+                let pending = self.pending_withdrawal(recipient).saturating_add(value);
+                self.pending_withdrawals.insert(recipient, &pending);
+                self.reserved = self.reserved.saturating_add(value);
+            } else {
+                // Every claim record is written before this call, so an asset
+                // that calls back into the contract from `transfer` finds the
+                // leaf already claimed. If the asset refuses the transfer, the
+                // claim stands and the payout is kept for `retry_claim`, so
+                // the recipient does not have to prove the leaf again.
+                match self.transfer_out(self.payout_address(recipient), value) {
+                    Err(Error::TransferFailed) => {
+                        let owed = self.owed(recipient).saturating_add(value);
+                        self.owed.insert(recipient, &owed);
+                        self.reserved = self.reserved.saturating_add(value);
+
+                        self.env().emit_event(PaymentDeferred {
+                            recipient,
+                            amount: value,
+                        });
+                    }
+                    result => result?,
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Internal: check and account for a claim payout of `value` to
+        /// `recipient`, returning the amount left after the claim fee.
+        fn account_payout(&mut self, recipient: Address, value: U256) -> Result<U256> {
+            self.check_payout(recipient)?;
+            self.last_payout_at
+                .insert(recipient, &self.env().block_timestamp());
+
+            self.add_claimed(value)?;
+            let value = self.net_payout(value)?;
+            let claimed_amount = self.claimed_amount(recipient).saturating_add(value);
+            self.claimed_amounts.insert(recipient, &claimed_amount);
+
+            Ok(value)
+        }
+
+        /// Internal: what a `value` payout leaves for the recipient after the
+        /// claim fee and, during the grace period, the late penalty.
+        ///
+        /// Both stay in the contract's balance and are recovered by a sweep.
+        fn net_payout(&self, value: U256) -> Result<U256> {
+            let mut value = value - self.claim_fee(value)?;
+
+            if self.env().block_timestamp() > self.campaign_end_time {
+                let penalty = value
+                    .checked_mul(U256::from(self.late_penalty_bps))
+                    .ok_or(Error::Overflow)?;
+                value -= penalty / U256::from(MAX_BPS);
+            }
+
+            Ok(value)
+        }
+
+        /// Internal: the part of a `value` payout withheld as the claim fee.
+        fn claim_fee(&self, value: U256) -> Result<U256> {
+            let fee = value
+                .checked_mul(U256::from(self.fee_bps))
+                .ok_or(Error::Overflow)?;
+
+            Ok(fee / U256::from(MAX_BPS))
+        }
+
+        /// Internal: ensure the campaign is not cancelled, claims are enabled
+        /// and not paused, and `recipient` is past their cooldown.
+        fn check_payout(&self, recipient: Address) -> Result<()> {
+            self.check_initialized()?;
+            if self.campaign_cancelled() {
+                return Err(Error::CampaignCancelled);
+            }
+            if self.paused {
+                return Err(Error::Paused);
+            }
+            if !self.claims_enabled {
+                return Err(Error::ClaimsDisabled);
+            }
+            if self.env().block_timestamp() < self.next_claim_available(recipient) {
+                return Err(Error::Cooldown);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: the address `recipient`'s tokens are transferred to.
+        fn payout_address(&self, recipient: Address) -> Address {
+            self.payout_overrides.get(recipient).unwrap_or(recipient)
+        }
+
+        /// Internal: scale a committed amount by `decimals_factor`.
+        fn scale_amount(&self, value: U256) -> Result<U256> {
+            value
+                .checked_mul(self.decimals_factor)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Internal: add `value` to `total_claimed`, failing rather than wrapping
+        /// or exceeding `max_distribution`.
+        fn add_claimed(&mut self, value: U256) -> Result<()> {
+            self.total_claimed = self.total_claimed_after(value)?;
+
+            Ok(())
+        }
+
+        /// Internal: `total_claimed` after paying `value`, if that neither
+        /// overflows nor exceeds `max_distribution`.
+        fn total_claimed_after(&self, value: U256) -> Result<U256> {
+            let total_claimed = self
+                .total_claimed
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            if total_claimed > self.max_distribution {
+                return Err(Error::DistributionCapExceeded);
+            }
+
+            Ok(total_claimed)
+        }
+
+        /// Internal: extend the campaign once if too little has been claimed.
+        ///
+        /// Returns whether the campaign was extended, in which case the sweep
+        /// that called this must not go ahead.
+        fn auto_extend(&mut self) -> Result<bool> {
+            if self.auto_extend_threshold_bps == 0 || self.auto_extended {
+                return Ok(false);
+            }
+
+            let claimed_bps = self.total_claimed.saturating_mul(U256::from(MAX_BPS));
+            let threshold = self
+                .expected_total
+                .saturating_mul(U256::from(self.auto_extend_threshold_bps));
+            if claimed_bps >= threshold {
+                return Ok(false);
+            }
+
+            let new_end_time = self
+                .env()
+                .block_timestamp()
+                .checked_add(self.auto_extend_duration)
+                .ok_or(Error::Overflow)?;
+            self.campaign_end_time = new_end_time;
+            self.auto_extended = true;
+
+            self.env().emit_event(CampaignAutoExtended { new_end_time });
+
+            Ok(true)
+        }
+
+        /// Internal: the contract's token balance minus what is owed to
+        /// claimants through `pending_withdrawals`.
+        fn unreserved_balance(&self) -> U256 {
+            let contract = self.env().address();
+            self.asset_contract
+                .balanceOf(contract)
+                .saturating_sub(self.reserved)
+        }
+
+        /// Internal: transfer the contract's unreserved token balance to `to`.
+        fn sweep_balance(&mut self, to: Address) -> Result<()> {
+            let amount = self.unreserved_balance();
+            if amount.is_zero() {
+                return Err(Error::NothingToSweep);
+            }
+
+            self.transfer_out(to, amount)?;
+
+            self.env().emit_event(Swept { to, amount });
+
+            Ok(())
+        }
+
+        /// Internal: count a new claim and log its timestamp.
+        ///
+        /// Block timestamps never decrease, so the log stays sorted; once
+        /// full, each claim overwrites the oldest entry.
+        fn count_claim(&mut self) {
+            self.claimed_count = self.claimed_count.saturating_add(1);
+
+            self.claim_times.insert(
+                self.claim_log_len % CLAIM_LOG_SIZE,
+                &self.env().block_timestamp(),
+            );
+            self.claim_log_len = self.claim_log_len.saturating_add(1);
+        }
+
+        /// Internal: the first position in the claim log, oldest first, whose
+        /// timestamp does not satisfy `before`, by binary search.
+        fn claim_log_partition(&self, before: impl Fn(u64) -> bool) -> u64 {
+            let mut low = self.claim_log_len.saturating_sub(CLAIM_LOG_SIZE);
+            let mut high = self.claim_log_len;
+
+            while low < high {
+                let mid = low + (high - low) / 2;
+                let timestamp = self
+                    .claim_times
+                    .get(mid % CLAIM_LOG_SIZE)
+                    .unwrap_or_default();
+                if before(timestamp) {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+
+            low
+        }
+
+        /// Internal: mark the leaf at `index` as claimed in the bitmap.
+        fn set_claimed_index(&mut self, index: u64) {
+            let word_index = index / 256;
+            let word = self.claimed_bitmap.get(word_index).unwrap_or_default();
+            let mask = U256::one() << (index % 256) as usize;
+            self.claimed_bitmap.insert(word_index, &(word | mask));
+        }
+
+        /// Internal: clear the bit for the leaf at `index` in the bitmap.
+        fn clear_claimed_index(&mut self, index: u64) {
+            let word_index = index / 256;
+            let word = self.claimed_bitmap.get(word_index).unwrap_or_default();
+            let mask = U256::one() << (index % 256) as usize;
+            self.claimed_bitmap.insert(word_index, &(word & !mask));
+        }
+
+        /// Internal: transfer `value` tokens from this contract to `to`.
+        ///
+        /// Refuses to start while another transfer is in flight, so an asset
+        /// calling back into the contract cannot move tokens against state
+        /// the outer call has not written back yet.
+        fn transfer_out(&mut self, to: Address, value: U256) -> Result<()> {
+            if self.transfer_lock.get().unwrap_or_default() {
+                return Err(Error::Reentrant);
+            }
+
+            let expected = self.expected_balance(|balance| balance.checked_sub(value));
+            self.transfer_lock.set(&true);
+            let transferred = self
+                .asset_contract
+                .call_mut()
+                .transfer(to, value)
+                .try_invoke();
+            self.transfer_lock.set(&false);
+
+            self.check_token_call(transferred, expected)
+        }
+
+        /// Internal: transfer `value` of Asset Hub asset `id` from this
+        /// contract to `to` through its ERC20 precompile, under the same
+        /// lock as [`transfer_out`].
+        fn transfer_asset_out(&mut self, id: AssetId, to: Address, value: U256) -> Result<()> {
+            if self.transfer_lock.get().unwrap_or_default() {
+                return Err(Error::Reentrant);
+            }
+
+            let mut precompile: ink::contract_ref!(Erc20, DefaultEnvironment, Sol) =
+                prefixed_address(PRECOMPILE_INDEX, id).into();
+            self.transfer_lock.set(&true);
+            let transferred = precompile.transfer(to, value);
+            self.transfer_lock.set(&false);
+
+            self.check_transfer(transferred)
+        }
+
+        /// Internal: interpret the result of a token transfer through an
+        /// Asset Hub precompile, which always reports success as `true`.
+        fn check_transfer(
+            &self,
+            transferred: core::result::Result<bool, assets::Error>,
+        ) -> Result<()> {
+            match transferred {
+                Ok(true) => Ok(()),
+                _ => Err(Error::TransferFailed),
+            }
+        }
+
+        /// Internal: interpret a token call on `asset_contract` that should
+        /// leave the contract holding `expected` tokens.
+        ///
+        /// The token has to return `true`; `false` or a revert is a refusal.
+        /// In lenient mode, a call whose output does not decode as a `bool`,
+        /// as with tokens whose `transfer` returns nothing, is accepted like
+        /// OpenZeppelin's `SafeERC20` does, but only once the contract's
+        /// balance shows the tokens actually moved.
+        fn check_token_call(&self, call: TokenCall, expected: Option<U256>) -> Result<()> {
+            match call {
+                Ok(Ok(Ok(true))) => Ok(()),
+                Err(_) if expected.is_some_and(|expected| expected == self.asset_balance()) => {
+                    Ok(())
+                }
+                _ => Err(Error::TransferFailed),
+            }
+        }
+
+        /// Internal: in lenient mode, the balance a token call should leave
+        /// the contract with, from `change` applied to the current one.
+        fn expected_balance(&self, change: impl FnOnce(U256) -> Option<U256>) -> Option<U256> {
+            if !self.lenient_transfers {
+                return None;
+            }
+
+            change(self.asset_balance())
+        }
+
+        /// Internal: the contract's own balance of the campaign asset.
+        fn asset_balance(&self) -> U256 {
+            self.asset_contract.balanceOf(self.env().address())
+        }
+
+        /// Internal: ensure caller is owner.
+        fn check_owner(&self) -> Result<()> {
+            if self.owner != self.env().caller() {
+                return Err(Error::Unauthorized);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: ensure `init` has configured a `new_uninitialized` campaign.
+        fn check_initialized(&self) -> Result<()> {
+            if !self.initialized {
+                return Err(Error::NotInitialized);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: ensure caller is the owner or the operator.
+        fn check_operator_or_owner(&self) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.owner && caller != self.operator {
+                return Err(Error::Unauthorized);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: pause or resume claims.
+        fn set_paused(&mut self, paused: bool) -> Result<()> {
+            self.check_operator_or_owner()?;
+
+            self.paused = paused;
+
+            self.env().emit_event(PauseUpdated { paused });
+
+            Ok(())
+        }
+
+        /// Internal: enable or disable claims.
+        fn set_claims_enabled(&mut self, enabled: bool) -> Result<()> {
+            self.check_owner()?;
+
+            self.claims_enabled = enabled;
+
+            self.env().emit_event(ClaimsEnabledUpdated { enabled });
+
+            Ok(())
+        }
+
+        /// Internal: ensure campaign is configured, has started and has not
+        /// yet ended.
+        fn check_campaign_ongoing(&self) -> Result<()> {
+            self.check_initialized()?;
+            self.check_window_at(self.env().block_timestamp())
+        }
+
+        /// Internal: when late claims stop, i.e. the end of the grace period.
+        fn claims_close_at(&self) -> u64 {
+            self.campaign_end_time.saturating_add(self.grace_period)
+        }
+
+        /// Internal: ensure `now` falls within the claim window, including
+        /// the grace period.
+        fn check_window_at(&self, now: u64) -> Result<()> {
+            if now < self.campaign_start_time {
+                return Err(Error::ClaimPeriodNotStarted);
+            }
+            if now > self.claims_close_at() {
+                return Err(Error::ClaimPeriodOver);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: whether the campaign is cancelled, including one that
+        /// missed its `fund_deadline` but has not been finalized yet.
+        fn campaign_cancelled(&self) -> bool {
+            self.cancelled || (self.fund_deadline_passed() && self.underfunded())
+        }
+
+        /// Internal: whether a `fund_deadline` is set and has passed.
+        fn fund_deadline_passed(&self) -> bool {
+            self.fund_deadline != 0 && self.env().block_timestamp() > self.fund_deadline
+        }
+
+        /// Internal: whether a `fund_deadline` is set and `total_funded` falls
+        /// short of `expected_total`.
+        fn underfunded(&self) -> bool {
+            self.fund_deadline != 0 && self.total_funded < self.expected_total
+        }
+
+        /// Internal: ensure campaign has ended, including its grace period.
+        fn check_campaign_ended(&self) -> Result<()> {
+            if self.env().block_timestamp() <= self.claims_close_at() {
+                return Err(Error::ClaimPeriodActive);
+            }
+
+            Ok(())
+        }
+
+        /// Internal: ensure the campaign and the sweep delay have both elapsed.
+        fn check_sweep_available(&self) -> Result<()> {
+            self.check_initialized()?;
+            if self.env().block_timestamp() <= self.sweep_available_at() {
+                return Err(Error::ClaimPeriodActive);
+            }
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Build a two-leaf tree with `algo` and check both proofs against it.
+        fn assert_two_leaf_tree_verifies(algo: HashAlgo) {
+            let leaf_a = hash(algo, &[0x11; 20], &U256::from(100).to_big_endian());
+            let leaf_b = hash(algo, &[0x22; 20], &U256::from(200).to_big_endian());
+            let root = hash(algo, &leaf_a, &leaf_b);
+
+            assert!(verify_proof(algo, leaf_a, &[leaf_b], 0, root));
+            assert!(verify_proof(algo, leaf_b, &[leaf_a], 1, root));
+            assert!(!verify_proof(algo, leaf_a, &[leaf_b], 1, root));
+        }
+
+        /// Deterministic xorshift64 generator, so property tests need no
+        /// extra dependencies and failures reproduce exactly.
+        struct Rng(u64);
+
+        impl Rng {
+            fn next(&mut self) -> u64 {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                self.0
+            }
+
+            fn hash(&mut self) -> [u8; 32] {
+                let mut out = [0u8; 32];
+                for chunk in out.chunks_exact_mut(8) {
+                    chunk.copy_from_slice(&self.next().to_be_bytes());
+                }
+                out
+            }
+        }
+
+        /// Build every level of a tree over `leaves`, pairing an odd last
+        /// node with itself like `scripts/utils/merkle_tree.ts`.
+        fn build_levels(algo: HashAlgo, leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+            let mut levels = vec![leaves];
+            while levels.last().unwrap().len() > 1 {
+                let next = levels
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|pair| hash(algo, &pair[0], pair.get(1).unwrap_or(&pair[0])))
+                    .collect();
+                levels.push(next);
+            }
+            levels
+        }
+
+        /// Collect the siblings of leaf `index` from `levels`, bottom up.
+        fn proof_for(levels: &[Vec<[u8; 32]>], index: usize) -> Vec<[u8; 32]> {
+            let mut index = index;
+            let mut proof = Vec::new();
+            for level in &levels[..levels.len() - 1] {
+                let sibling = index ^ 1;
+                proof.push(*level.get(sibling).unwrap_or(&level[index]));
+                index /= 2;
+            }
+            proof
+        }
+
+        /// Build the multiproof for the leaves at ascending `indices`, like
+        /// `MerkleTree.getMultiProof` in `scripts/utils/merkle_tree.ts`.
+        fn multiproof_for(levels: &[Vec<[u8; 32]>], indices: &[u64]) -> (Vec<[u8; 32]>, Vec<bool>) {
+            let mut proof = Vec::new();
+            let mut flags = Vec::new();
+            let mut nodes = indices.to_vec();
+
+            for level in &levels[..levels.len() - 1] {
+                let mut parents = Vec::new();
+                let mut i = 0;
+                while i < nodes.len() {
+                    let index = nodes[i];
+                    if nodes.get(i + 1) == Some(&(index ^ 1)) {
+                        flags.push(true);
+                        i += 2;
+                    } else {
+                        flags.push(false);
+                        let sibling = (index ^ 1) as usize;
+                        proof.push(*level.get(sibling).unwrap_or(&level[index as usize]));
+                        i += 1;
+                    }
+                    parents.push(index / 2);
+                }
+                nodes = parents;
+            }
+
+            (proof, flags)
+        }
+
+        /// Deploy an airdrop from a non-zero caller with a far-off end time.
+        fn new_airdrop() -> MerkleAirdrop {
+            new_airdrop_ending_at(MAX_DURATION)
+        }
+
+        /// Deploy an airdrop from a non-zero caller ending at `campaign_end_time`.
+        fn new_airdrop_ending_at(campaign_end_time: u64) -> MerkleAirdrop {
+            ink::env::test::set_caller(Address::from([0x01; 20]));
+
+            MerkleAirdrop::new(
+                Address::from([0xAA; 20]),
+                CampaignConfig {
+                    root: [0x11; 32],
+                    campaign_end_time,
+                    num_leaves: 2,
+                    ..CampaignConfig::default()
+                },
+            )
+        }
+
+        #[test]
+        fn keccak_proof_verifies() {
+            assert_two_leaf_tree_verifies(HashAlgo::Keccak256);
+        }
+
+        #[test]
+        fn blake2_proof_verifies() {
+            assert_two_leaf_tree_verifies(HashAlgo::Blake2x256);
+        }
+
+        #[test]
+        fn sha2_proof_verifies() {
+            assert_two_leaf_tree_verifies(HashAlgo::Sha2x256);
+        }
+
+        #[test]
+        fn sorted_pair_order_does_not_depend_on_argument_order() {
+            let low = [0x11; 32];
+            let high = [0x22; 32];
+
+            let parent = hash_sorted_pair(HashAlgo::Keccak256, &low, &high);
+            assert_eq!(parent, hash(HashAlgo::Keccak256, &low, &high));
+            assert_eq!(hash_sorted_pair(HashAlgo::Keccak256, &high, &low), parent);
+        }
+
+        #[test]
+        fn identical_sorted_leaves_have_a_well_defined_parent() {
+            let leaf = hash(
+                HashAlgo::Keccak256,
+                &[0x11; 20],
+                &U256::from(100).to_big_endian(),
+            );
+            // Matches `MerkleTree.hashSortedPair(leaf, leaf)` off-chain.
+            let root = hash(HashAlgo::Keccak256, &leaf, &leaf);
+
+            assert_eq!(hash_sorted_pair(HashAlgo::Keccak256, &leaf, &leaf), root);
+            assert!(verify_proof_sorted(
+                HashAlgo::Keccak256,
+                leaf,
+                &[leaf],
+                root
+            ));
+            assert!(!verify_proof_sorted(
+                HashAlgo::Keccak256,
+                leaf,
+                &[leaf],
+                leaf
+            ));
+        }
+
+        #[test]
+        fn proof_shorter_than_the_leaf_depth_is_rejected() {
+            let algo = HashAlgo::Keccak256;
+            let leaves: Vec<[u8; 32]> = (0u8..4)
+                .map(|n| hash(algo, &[n; 20], &U256::from(100).to_big_endian()))
+                .collect();
+            let left = hash(algo, &leaves[0], &leaves[1]);
+            let right = hash(algo, &leaves[2], &leaves[3]);
+            let root = hash(algo, &left, &right);
+
+            assert!(verify_proof(algo, leaves[2], &[leaves[3], left], 2, root));
+            // One level short: hashes to `right`, but index 2 is not its
+            // position one level up.
+            assert!(!verify_proof(algo, leaves[2], &[leaves[3]], 2, right));
+        }
+
+        #[test]
+        fn random_trees_verify_every_leaf() {
+            let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+            for algo in [
+                HashAlgo::Keccak256,
+                HashAlgo::Blake2x256,
+                HashAlgo::Sha2x256,
+            ] {
+                // Covers the single-leaf tree and both odd and even widths.
+                for num_leaves in 1..=17 {
+                    let leaves: Vec<[u8; 32]> = (0..num_leaves).map(|_| rng.hash()).collect();
+                    let levels = build_levels(algo, leaves.clone());
+                    let root = levels.last().unwrap()[0];
+
+                    for (index, leaf) in leaves.iter().enumerate() {
+                        let proof = proof_for(&levels, index);
+                        assert!(
+                            verify_proof(algo, *leaf, &proof, index as u64, root),
+                            "leaf {index} of {num_leaves}"
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn single_leaf_tree_is_its_own_root() {
+            let leaf = Rng(7).hash();
+
+            assert!(verify_proof(HashAlgo::Keccak256, leaf, &[], 0, leaf));
+            assert!(!verify_proof(HashAlgo::Keccak256, leaf, &[], 1, leaf));
+            assert!(!verify_proof(HashAlgo::Keccak256, leaf, &[leaf], 0, leaf));
+        }
+
+        #[test]
+        fn mutated_proofs_are_rejected() {
+            let algo = HashAlgo::Keccak256;
+            let mut rng = Rng(0xDEAD_BEEF_CAFE_F00D);
+            for num_leaves in 2..=17 {
+                let leaves: Vec<[u8; 32]> = (0..num_leaves).map(|_| rng.hash()).collect();
+                let levels = build_levels(algo, leaves.clone());
+                let root = levels.last().unwrap()[0];
+
+                for (index, leaf) in leaves.iter().enumerate() {
+                    let proof = proof_for(&levels, index);
+                    let position = index as u64;
+
+                    // Any other in-range index.
+                    let other = ((index + 1) % num_leaves) as u64;
+                    assert!(!verify_proof(algo, *leaf, &proof, other, root));
+
+                    // A flipped bit in any sibling.
+                    for level in 0..proof.len() {
+                        let mut mutated = proof.clone();
+                        mutated[level][(rng.next() % 32) as usize] ^= 1 << (rng.next() % 8);
+                        assert!(!verify_proof(algo, *leaf, &mutated, position, root));
+                    }
+
+                    // A different leaf, a truncated proof and an extended proof.
+                    assert!(!verify_proof(algo, rng.hash(), &proof, position, root));
+                    assert!(!verify_proof(
+                        algo,
+                        *leaf,
+                        &proof[..proof.len() - 1],
+                        position,
+                        root
+                    ));
+                    let mut extended = proof.clone();
+                    extended.push(rng.hash());
+                    assert!(!verify_proof(algo, *leaf, &extended, position, root));
+                }
+            }
+        }
+
+        #[test]
+        fn multiproofs_verify_any_subset_of_leaves() {
+            let algo = HashAlgo::Keccak256;
+            let mut rng = Rng(0x0123_4567_89AB_CDEF);
+            let leaves: Vec<[u8; 32]> = (0..9).map(|_| rng.hash()).collect();
+            let levels = build_levels(algo, leaves.clone());
+            let root = levels.last().unwrap()[0];
+
+            for indices in [
+                &[0][..],
+                &[1, 2],
+                &[0, 1, 2, 3],
+                &[3, 8],
+                &[7, 8],
+                &[0, 1, 2, 3, 4, 5, 6, 7, 8],
+            ] {
+                let (proof, flags) = multiproof_for(&levels, indices);
+                let mut selected: Vec<(u64, [u8; 32])> =
+                    indices.iter().map(|&i| (i, leaves[i as usize])).collect();
+                assert!(
+                    verify_multiproof(algo, &selected, &proof, &flags, root),
+                    "{indices:?}"
+                );
+
+                // A leaf at the wrong index.
+                selected[0].0 ^= 1;
+                assert!(!verify_multiproof(algo, &selected, &proof, &flags, root));
+                selected[0].0 ^= 1;
+
+                // A flipped flag, or a corrupted proof element.
+                let mut bad_flags = flags.clone();
+                bad_flags[0] = !bad_flags[0];
+                assert!(!verify_multiproof(
+                    algo, &selected, &proof, &bad_flags, root
+                ));
+                if let Some(first) = proof.first() {
+                    let mut bad_proof = proof.clone();
+                    bad_proof[0] = hash(algo, first, first);
+                    assert!(!verify_multiproof(
+                        algo, &selected, &bad_proof, &flags, root
+                    ));
+                }
+            }
+
+            assert!(!verify_multiproof(algo, &[], &[], &[], root));
+        }
+
+        #[test]
+        fn proof_does_not_verify_under_another_algorithm() {
+            let leaf_a = hash(HashAlgo::Keccak256, &[0x11; 20], &[0u8; 32]);
+            let leaf_b = hash(HashAlgo::Keccak256, &[0x22; 20], &[0u8; 32]);
+            let root = hash(HashAlgo::Keccak256, &leaf_a, &leaf_b);
+
+            assert!(!verify_proof(
+                HashAlgo::Sha2x256,
+                leaf_a,
+                &[leaf_b],
+                0,
+                root
+            ));
+            assert!(!verify_proof(
+                HashAlgo::Blake2x256,
+                leaf_a,
+                &[leaf_b],
+                0,
+                root
+            ));
+        }
+
+        #[ink::test]
+        fn total_claimed_overflow_is_an_error() {
+            let mut airdrop = new_airdrop();
+            airdrop.total_claimed = U256::MAX - U256::from(1);
+
+            assert_eq!(airdrop.add_claimed(U256::from(1)), Ok(()));
+            assert_eq!(airdrop.total_claimed(), U256::MAX);
+            assert_eq!(airdrop.add_claimed(U256::from(1)), Err(Error::Overflow));
+            assert_eq!(airdrop.total_claimed(), U256::MAX);
+        }
+
+        #[ink::test]
+        fn vested_bps_tracks_the_claim_window() {
+            let mut airdrop = new_airdrop();
+            airdrop.campaign_start_time = 1_000;
+            airdrop.campaign_end_time = 3_000;
+
+            for (now, expected) in [
+                (0, 0),
+                (1_000, 0),
+                (1_500, 2_500),
+                (2_000, 5_000),
+                (3_000, MAX_BPS),
+                (9_000, MAX_BPS),
+            ] {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(now);
+                assert_eq!(airdrop.vested_bps(), expected, "at {now}");
+            }
+        }
+
+        #[ink::test]
+        fn claim_rejects_index_past_the_last_leaf() {
+            let mut airdrop = new_airdrop();
+
+            assert_eq!(
+                airdrop.claim(U256::from(100), Vec::new(), airdrop.num_leaves()),
+                Err(Error::IndexOutOfRange)
+            );
+            assert_eq!(
+                airdrop.claim(U256::from(100), Vec::new(), u64::MAX),
+                Err(Error::IndexOutOfRange)
+            );
+        }
+
+        #[ink::test]
+        fn tree_depth_rounds_up_to_a_full_level() {
+            let mut airdrop = new_airdrop();
+
+            for (num_leaves, depth) in [(1, 0), (2, 1), (3, 2), (4, 2), (5, 3), (1 << 20, 20)] {
+                airdrop.num_leaves = num_leaves;
+                assert_eq!(airdrop.tree_depth(), depth, "for {num_leaves} leaves");
+                assert_eq!(u32::from(airdrop.required_depth(num_leaves)), depth);
+            }
+            assert_eq!(airdrop.required_depth(u64::MAX), 64);
+        }
+
+        #[ink::test]
+        fn why_invalid_separates_short_proofs_from_wrong_roots() {
+            let mut airdrop = new_airdrop();
+            airdrop.num_leaves = 4;
+            let recipient = Address::from([0x22; 20]);
+
+            assert_eq!(
+                airdrop.why_invalid(recipient, U256::from(100), vec![[0x33; 32]], 0),
+                RejectionReason::ProofTooShort
+            );
+            assert_eq!(
+                airdrop.why_invalid(recipient, U256::from(100), vec![[0x33; 32]; 2], 0),
+                RejectionReason::RootMismatch
+            );
+            assert_eq!(
+                airdrop.why_invalid(recipient, U256::from(100), Vec::new(), 4),
+                RejectionReason::IndexOutOfRange
+            );
+        }
+
+        #[ink::test]
+        fn under_claimed_campaign_extends_only_once() {
+            let mut airdrop = new_airdrop();
+            airdrop.auto_extend_threshold_bps = 5_000;
+            airdrop.auto_extend_duration = 1_000;
+            airdrop.expected_total = U256::from(1_000);
+            airdrop.total_claimed = U256::from(499);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10_000);
+
+            assert_eq!(airdrop.auto_extend(), Ok(true));
+            assert_eq!(airdrop.campaign_end_time(), 11_000);
+            assert_eq!(airdrop.auto_extend(), Ok(false));
+            assert_eq!(airdrop.campaign_end_time(), 11_000);
+        }
+
+        #[ink::test]
+        fn total_claimed_cannot_exceed_max_distribution() {
+            let mut airdrop = new_airdrop();
+            airdrop.max_distribution = U256::from(1_000);
+
+            assert_eq!(airdrop.add_claimed(U256::from(1_000)), Ok(()));
+            assert_eq!(
+                airdrop.add_claimed(U256::from(1)),
+                Err(Error::DistributionCapExceeded)
+            );
+            assert_eq!(airdrop.total_claimed(), U256::from(1_000));
+        }
+
+        #[ink::test]
+        fn fund_rejects_deposits_after_the_campaign_ends() {
+            let mut airdrop = new_airdrop();
+            airdrop.campaign_end_time = 1_000;
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_001);
+            assert_eq!(airdrop.fund(U256::from(1)), Err(Error::ClaimPeriodOver));
+        }
+
+        #[ink::test]
+        fn mark_claimed_is_only_allowed_before_the_first_claim() {
+            let mut airdrop = new_airdrop();
+            let migrated = Address::from([0x22; 20]);
+
+            assert_eq!(airdrop.mark_claimed(vec![migrated]), Ok(()));
+            assert!(airdrop.is_claimed(migrated));
+
+            airdrop.total_claimed = U256::from(1);
+            assert_eq!(
+                airdrop.mark_claimed(vec![Address::from([0x33; 20])]),
+                Err(Error::ClaimsAlreadyStarted)
+            );
+        }
+
+        #[ink::test]
+        fn repeated_payouts_wait_for_the_cooldown() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            airdrop.claim_cooldown = 100;
+            let recipient = Address::from([0x22; 20]);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(airdrop.pay_out(recipient, U256::from(1)), Ok(()));
+            assert_eq!(airdrop.next_claim_available(recipient), 1_100);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_099);
+            assert_eq!(
+                airdrop.pay_out(recipient, U256::from(1)),
+                Err(Error::Cooldown)
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_100);
+            assert_eq!(airdrop.pay_out(recipient, U256::from(1)), Ok(()));
+        }
+
+        #[ink::test]
+        fn replaced_roots_accept_proofs_only_during_the_grace_period() {
+            let mut airdrop = new_airdrop();
+            let first_root = airdrop.root();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(airdrop.update_root([0u8; 32], 4), Err(Error::InvalidRoot));
+            assert_eq!(airdrop.update_root([0x22; 32], 0), Err(Error::InvalidRoot));
+            assert_eq!(airdrop.update_root([0x22; 32], 4), Ok(()));
+            assert_eq!(airdrop.root_index(), 1);
+            assert_eq!(airdrop.num_leaves(), 4);
+            assert_eq!(airdrop.root_at(1), Ok([0x22; 32]));
+            assert_eq!(airdrop.root_at(0), Ok(first_root));
+            assert_eq!(airdrop.root_at(2), Err(Error::RootExpired));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                1_000 + ROOT_GRACE_PERIOD + 1,
+            );
+            assert_eq!(airdrop.root_at(0), Err(Error::RootExpired));
+            assert_eq!(airdrop.root_at(1), Ok([0x22; 32]));
+        }
+
+        #[ink::test]
+        fn operator_can_pause_but_not_sweep() {
+            let mut airdrop = new_airdrop();
+            let operator = Address::from([0x22; 20]);
+            assert_eq!(airdrop.set_operator(operator), Ok(()));
+
+            ink::env::test::set_caller(operator);
+            assert_eq!(airdrop.pause(), Ok(()));
+            assert!(airdrop.is_paused());
+            assert_eq!(airdrop.update_root([0x33; 32], 2), Ok(()));
+            assert_eq!(airdrop.sweep_unclaimed(), Err(Error::Unauthorized));
+            assert_eq!(airdrop.set_operator(operator), Err(Error::Unauthorized));
+
+            airdrop.pull_payments = true;
+            assert_eq!(
+                airdrop.pay_out(Address::from([0x44; 20]), U256::from(1)),
+                Err(Error::Paused)
+            );
+        }
+
+        #[ink::test]
+        fn multi_asset_leaf_commits_to_every_asset_in_order() {
+            let airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            let first = AssetAmount {
+                id: 1,
+                amount: U256::from(100),
+            };
+            let second = AssetAmount {
+                id: 2,
+                amount: U256::from(200),
+            };
+
+            let mut right = [0u8; 36];
+            right[..4].copy_from_slice(&1u32.to_be_bytes());
+            right[4..].copy_from_slice(&U256::from(100).to_big_endian());
+            assert_eq!(
+                airdrop.multi_asset_leaf(recipient, &[first]),
+                hash(
+                    HashAlgo::Keccak256,
+                    &[&[LEAF_MULTI_ASSET][..], recipient.as_bytes()].concat(),
+                    &right
+                )
+            );
+            assert_ne!(
+                airdrop.multi_asset_leaf(recipient, &[first, second]),
+                airdrop.multi_asset_leaf(recipient, &[second, first])
+            );
+        }
+
+        #[ink::test]
+        fn preview_claim_reports_the_errors_claim_would() {
+            let mut airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+
+            assert_eq!(
+                airdrop.preview_claim(recipient, U256::from(100), Vec::new(), 4),
+                Err(Error::IndexOutOfRange)
+            );
+            assert_eq!(
+                airdrop.preview_claim(recipient, U256::from(100), Vec::new(), 0),
+                Err(Error::InvalidProof)
+            );
+
+            airdrop.paused = true;
+            airdrop.root = airdrop.leaf(recipient, U256::from(100));
+            airdrop.num_leaves = 1;
+            assert_eq!(
+                airdrop.preview_claim(recipient, U256::from(100), Vec::new(), 0),
+                Err(Error::Paused)
+            );
+
+            airdrop.paused = false;
+            assert_eq!(
+                airdrop.preview_claim(recipient, U256::from(100), Vec::new(), 0),
+                Ok(U256::from(100))
+            );
+        }
+
+        #[ink::test]
+        fn payout_override_redirects_until_cleared() {
+            let mut airdrop = new_airdrop();
+            let wallet = Address::from([0x22; 20]);
+            let custody = Address::from([0x33; 20]);
+
+            assert_eq!(airdrop.set_payout_override(wallet, custody), Ok(()));
+            assert_eq!(airdrop.payout_override(wallet), custody);
+            assert_eq!(airdrop.payout_address(wallet), custody);
+
+            assert_eq!(airdrop.set_payout_override(wallet, Address::zero()), Ok(()));
+            assert_eq!(airdrop.payout_address(wallet), wallet);
+        }
+
+        #[ink::test]
+        fn claim_value_must_match_the_leaf_exactly() {
+            let mut airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            let sibling = airdrop.leaf(Address::from([0x33; 20]), U256::from(200));
+            airdrop.root = hash(
+                HashAlgo::Keccak256,
+                &airdrop.leaf(recipient, U256::from(100)),
+                &sibling,
+            );
+
+            assert_eq!(
+                airdrop.validate_claim(recipient, U256::from(100), &[sibling], 0),
+                Ok(())
+            );
+            for value in [U256::from(99), U256::from(101)] {
+                assert_eq!(
+                    airdrop.validate_claim(recipient, value, &[sibling], 0),
+                    Err(Error::InvalidProof)
+                );
+            }
+        }
+
+        #[ink::test]
+        fn init_configures_an_uninitialized_campaign_once() {
+            ink::env::test::set_caller(Address::from([0x01; 20]));
+            let mut airdrop = MerkleAirdrop::new_uninitialized();
+            assert!(!airdrop.is_active());
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(airdrop.fund(U256::from(100)), Err(Error::NotInitialized));
+            assert_eq!(airdrop.sweep_unclaimed(), Err(Error::NotInitialized));
+            assert_eq!(airdrop.refund(), Err(Error::NotInitialized));
+            assert_eq!(
+                airdrop.update_root([0x22; 32], 2),
+                Err(Error::NotInitialized)
+            );
+            assert_eq!(
+                airdrop.claim(U256::from(100), Vec::new(), 0),
+                Err(Error::NotInitialized)
+            );
+            assert_eq!(
+                airdrop.init([0x11; 32], 1_000, 2, Address::from([0xAA; 20])),
+                Err(Error::InvalidEndTime)
+            );
+            assert_eq!(
+                airdrop.init([0x11; 32], 2_000, 2, Address::zero()),
+                Err(Error::ZeroAddress)
+            );
+            assert_eq!(
+                airdrop.init([0x11; 32], 2_000, 2, Address::from([0xAA; 20])),
+                Ok(())
+            );
+            assert_eq!(airdrop.root(), [0x11; 32]);
+            assert_eq!(airdrop.root_history(), vec![([0x11; 32], 1_000)]);
+            assert!(airdrop.is_active());
+
+            assert_eq!(
+                airdrop.init([0x22; 32], 3_000, 2, Address::from([0xAA; 20])),
+                Err(Error::AlreadyInitialized)
+            );
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "Campaign must not run longer than MAX_DURATION")]
+        fn new_rejects_a_campaign_longer_than_max_duration() {
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            new_airdrop_ending_at(1_000 + MAX_DURATION + 1);
+        }
+
+        #[ink::test]
+        fn claim_all_vested_pays_only_the_new_vested_part() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+            airdrop.pull_payments = true;
+            let recipient = Address::from([0x01; 20]);
+            airdrop.root = airdrop.nonce_leaf(recipient, 7, U256::from(100));
+            airdrop.num_leaves = 1;
+            let entry = NonceClaim {
+                value: U256::from(100),
+                nonce: 7,
+                proof: Vec::new(),
+                index: 0,
+            };
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(250);
+            assert_eq!(
+                airdrop.claim_all_vested(vec![entry.clone()]),
+                Ok(U256::from(25))
+            );
+            assert_eq!(
+                airdrop.claim_all_vested(vec![entry.clone()]),
+                Ok(U256::zero())
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(
+                airdrop.claim_all_vested(vec![entry.clone()]),
+                Ok(U256::from(75))
+            );
+            assert!(airdrop.is_claimed_nonce(recipient, 7));
+            assert_eq!(airdrop.claim_all_vested(vec![entry]), Ok(U256::zero()));
+            assert_eq!(airdrop.pending_withdrawal(recipient), U256::from(100));
+        }
+
+        #[ink::test]
+        fn sweep_and_transfer_ownership_checks_before_sweeping() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+
+            assert_eq!(
+                airdrop.sweep_and_transfer_ownership(Address::zero()),
+                Err(Error::ZeroAddress)
+            );
+            assert_eq!(
+                airdrop.sweep_and_transfer_ownership(Address::from([0x22; 20])),
+                Err(Error::ClaimPeriodActive)
+            );
+
+            ink::env::test::set_caller(Address::from([0x22; 20]));
+            assert_eq!(
+                airdrop.sweep_and_transfer_ownership(Address::from([0x22; 20])),
+                Err(Error::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn claim_with_referrer_rejects_self_referral_and_oversized_bonuses() {
+            let mut airdrop = new_airdrop();
+            airdrop.max_referrer_bps = 500;
+            let caller = Address::from([0x01; 20]);
+
+            assert_eq!(
+                airdrop.claim_with_referrer(U256::from(100), Vec::new(), 0, Address::zero(), 100),
+                Err(Error::ZeroReferrer)
+            );
+            assert_eq!(
+                airdrop.claim_with_referrer(U256::from(100), Vec::new(), 0, caller, 100),
+                Err(Error::SelfReferral)
+            );
+            assert_eq!(
+                airdrop.claim_with_referrer(
+                    U256::from(100),
+                    Vec::new(),
+                    0,
+                    Address::from([0x22; 20]),
+                    501
+                ),
+                Err(Error::ReferralTooLarge)
+            );
+        }
+
+        #[ink::test]
+        fn fee_is_withheld_and_capped() {
+            let mut airdrop = new_airdrop();
+            airdrop.max_fee_bps = 100;
+            airdrop.pull_payments = true;
+            let recipient = Address::from([0x22; 20]);
+
+            assert_eq!(airdrop.set_fee_bps(101), Err(Error::FeeTooHigh));
+            assert_eq!(airdrop.set_fee_bps(100), Ok(()));
+
+            assert_eq!(airdrop.pay_out(recipient, U256::from(1_000)), Ok(()));
+            assert_eq!(airdrop.pending_withdrawal(recipient), U256::from(990));
+            assert_eq!(airdrop.total_claimed(), U256::from(1_000));
+        }
+
+        #[ink::test]
+        fn packed_proof_must_be_whole_hashes() {
+            let mut airdrop = new_airdrop();
+
+            assert_eq!(
+                airdrop.claim_packed(U256::from(1), vec![0u8; 33], 0),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                unpack_proof(&[[0x01; 32], [0x02; 32]].concat()),
+                Some(vec![[0x01; 32], [0x02; 32]])
+            );
+        }
+
+        #[ink::test]
+        fn escrowed_claim_stays_locked_until_unlock_time() {
+            let mut airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            ink::env::test::set_caller(recipient);
+
+            airdrop.root = airdrop.escrow_leaf(recipient, U256::from(100), 5_000);
+
+            airdrop.num_leaves = 1;
+            assert_eq!(
+                airdrop.claim_to_escrow(U256::from(100), Vec::new(), 0, 4_000),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                airdrop.claim_to_escrow(U256::from(100), Vec::new(), 0, 5_000),
+                Ok(())
+            );
+
+            assert_eq!(
+                airdrop.escrows.get(recipient),
+                Some((U256::from(100), 5_000))
+            );
+            assert_eq!(airdrop.reserved, U256::from(100));
+            assert!(airdrop.is_claimed(recipient));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(4_999);
+            assert_eq!(airdrop.withdraw_escrow(), Err(Error::EscrowLocked));
+        }
+
+        #[ink::test]
+        fn zero_value_leaf_cannot_be_claimed() {
+            let mut airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            ink::env::test::set_caller(recipient);
+            airdrop.root = airdrop.leaf(recipient, U256::zero());
+            airdrop.num_leaves = 1;
+
+            assert_eq!(
+                airdrop.claim(U256::zero(), Vec::new(), 0),
+                Err(Error::AmountCannotBeZero)
+            );
+            assert!(!airdrop.is_claimed(recipient));
+            assert_eq!(
+                airdrop.why_invalid(recipient, U256::zero(), Vec::new(), 0),
+                RejectionReason::ZeroAmount
+            );
+
+            // Every other kind of leaf is checked the same way.
+            airdrop.root = airdrop.escrow_leaf(recipient, U256::zero(), 5_000);
+            assert_eq!(
+                airdrop.claim_to_escrow(U256::zero(), Vec::new(), 0, 5_000),
+                Err(Error::AmountCannotBeZero)
+            );
+            airdrop.root = airdrop.nonce_leaf(recipient, 7, U256::zero());
+            assert_eq!(
+                airdrop.claim_with_nonce(U256::zero(), 7, Vec::new(), 0),
+                Err(Error::AmountCannotBeZero)
+            );
+            assert!(!airdrop.is_claimed(recipient));
+        }
+
+        #[ink::test]
+        fn disabled_claims_are_rejected_until_enabled() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            let recipient = Address::from([0x22; 20]);
+            assert!(airdrop.claims_enabled());
+
+            assert_eq!(airdrop.disable_claims(), Ok(()));
+            assert!(!airdrop.claims_enabled());
+            assert_eq!(
+                airdrop.pay_out(recipient, U256::from(100)),
+                Err(Error::ClaimsDisabled)
+            );
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(airdrop.enable_claims(), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller(Address::from([0x01; 20]));
+            assert_eq!(airdrop.enable_claims(), Ok(()));
+            assert_eq!(airdrop.pay_out(recipient, U256::from(100)), Ok(()));
+        }
+
+        #[ink::test]
+        fn retry_asset_claim_requires_an_owed_amount() {
+            let mut airdrop = new_airdrop();
+            let caller = Address::from([0x01; 20]);
+            assert_eq!(airdrop.retry_asset_claim(7), Err(Error::NothingOwed));
+
+            airdrop.owed_assets.insert((caller, 8), &U256::from(100));
+            assert_eq!(airdrop.owed_asset(caller, 8), U256::from(100));
+            assert_eq!(airdrop.retry_asset_claim(7), Err(Error::NothingOwed));
+        }
+
+        #[ink::test]
+        fn sweep_all_requires_owner_and_ended_campaign() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+            airdrop.sweep_delay = 100;
+
+            assert_eq!(airdrop.sweep_all(vec![1]), Err(Error::ClaimPeriodActive));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_050);
+            assert_eq!(airdrop.sweep_all(vec![1]), Err(Error::ClaimPeriodActive));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_101);
+            ink::env::test::set_caller(Address::from([0x22; 20]));
+            assert_eq!(airdrop.sweep_all(vec![1]), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller(Address::from([0x01; 20]));
+            assert_eq!(airdrop.sweep_all(Vec::new()), Ok(Vec::new()));
+        }
+
+        #[ink::test]
+        fn can_claim_is_false_for_each_failing_check() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+            let recipient = Address::from([0x22; 20]);
+            let value = U256::from(100);
+            airdrop.campaign_start_time = 10;
+            airdrop.root = airdrop.leaf(recipient, value);
+            airdrop.num_leaves = 1;
+
+            // Not started yet.
+            assert!(!airdrop.can_claim(recipient, value, Vec::new(), 0));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10);
+            assert!(airdrop.can_claim(recipient, value, Vec::new(), 0));
+
+            // Wrong value, i.e. a proof that does not verify.
+            assert!(!airdrop.can_claim(recipient, U256::from(101), Vec::new(), 0));
+
+            // Already claimed.
+            airdrop.claimed.insert(recipient, &true);
+            assert!(!airdrop.can_claim(recipient, value, Vec::new(), 0));
+            airdrop.claimed.remove(recipient);
+
+            // Window closed.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_001);
+            assert!(!airdrop.can_claim(recipient, value, Vec::new(), 0));
+        }
+
+        #[ink::test]
+        fn root_history_starts_with_the_initial_root() {
+            let mut airdrop = new_airdrop();
+            assert_eq!(airdrop.root_history(), vec![([0x11; 32], 0)]);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5);
+            assert_eq!(airdrop.update_root([0x22; 32], 2), Ok(()));
+            assert_eq!(
+                airdrop.root_history(),
+                vec![([0x11; 32], 0), ([0x22; 32], 5)]
+            );
+        }
+
+        #[ink::test]
+        fn root_history_keeps_the_latest_updates() {
+            let mut airdrop = new_airdrop();
+
+            for n in 0..=ROOT_LOG_SIZE as u64 {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(n);
+                assert_eq!(airdrop.update_root([n as u8 + 1; 32], 2), Ok(()));
+            }
+
+            let history = airdrop.root_history();
+            assert_eq!(history.len(), ROOT_LOG_SIZE as usize);
+            assert_eq!(history[0], ([2; 32], 1));
+            assert_eq!(
+                history[ROOT_LOG_SIZE as usize - 1],
+                ([ROOT_LOG_SIZE as u8 + 1; 32], ROOT_LOG_SIZE as u64)
+            );
+        }
+
+        #[ink::test]
+        fn share_claims_pay_from_the_finalized_total() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            airdrop.total_funded = U256::from(10_000);
+            let recipient = Address::from([0x22; 20]);
+            airdrop.root = airdrop.tagged_leaf(LEAF_SHARE, recipient, &2_500u16.to_be_bytes());
+            airdrop.num_leaves = 1;
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(
+                airdrop.claim_share(2_500, Vec::new(), 0),
+                Err(Error::FundingNotFinalized)
+            );
+            assert_eq!(airdrop.finalize_funding(), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller(Address::from([0x01; 20]));
+            assert_eq!(airdrop.finalize_funding(), Ok(()));
+            assert_eq!(airdrop.fund(U256::from(1)), Err(Error::FundingClosed));
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(airdrop.claim_share(2_500, Vec::new(), 0), Ok(()));
+            assert_eq!(airdrop.pending_withdrawal(recipient), U256::from(2_500));
+        }
+
+        #[ink::test]
+        fn node_hash_matches_the_proof_hasher() {
+            let mut airdrop = new_airdrop();
+            let (left, right) = ([0x01; 32], [0x02; 32]);
+
+            assert_eq!(
+                airdrop.node_hash(left, right),
+                hash(HashAlgo::Keccak256, &left, &right)
+            );
+            assert_ne!(
+                airdrop.node_hash(left, right),
+                airdrop.node_hash(right, left)
+            );
+
+            airdrop.hash_algo = HashAlgo::Sha2x256;
+            assert!(verify_proof(
+                HashAlgo::Sha2x256,
+                left,
+                &[right],
+                0,
+                airdrop.node_hash(left, right)
+            ));
+        }
+
+        #[ink::test]
+        fn state_follows_the_window_pause_and_cancellation() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+            airdrop.campaign_start_time = 10;
+            assert_eq!(airdrop.state(), CampaignState::NotStarted);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(airdrop.state(), CampaignState::Active);
+            assert!(airdrop.is_active());
+
+            airdrop.paused = true;
+            assert_eq!(airdrop.state(), CampaignState::Paused);
+            assert!(!airdrop.is_active());
+            airdrop.paused = false;
+            airdrop.claims_enabled = false;
+            assert_eq!(airdrop.state(), CampaignState::Paused);
+            assert!(!airdrop.is_active());
+
+            airdrop.claims_enabled = true;
+            airdrop.cancelled = true;
+            assert_eq!(airdrop.state(), CampaignState::Cancelled);
+            assert!(!airdrop.is_active());
+            airdrop.cancelled = false;
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_001);
+            assert_eq!(airdrop.state(), CampaignState::Ended);
+
+            airdrop.cancelled = true;
+            assert_eq!(airdrop.state(), CampaignState::Cancelled);
+        }
+
+        #[ink::test]
+        fn late_claims_pay_a_penalty_until_grace_ends() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+            airdrop.pull_payments = true;
+            airdrop.grace_period = 1_000;
+            airdrop.late_penalty_bps = 2_000;
+            let recipient = Address::from([0x22; 20]);
+            airdrop.root = airdrop.leaf(recipient, U256::from(100));
+            airdrop.num_leaves = 1;
+            ink::env::test::set_caller(recipient);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_001);
+            assert_eq!(
+                airdrop.claim(U256::from(100), Vec::new(), 0),
+                Err(Error::ClaimPeriodOver)
+            );
+            assert_eq!(airdrop.sweep_available_at(), 2_000);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_500);
+            assert_eq!(airdrop.claim(U256::from(100), Vec::new(), 0), Ok(()));
+            assert_eq!(airdrop.pending_withdrawal(recipient), U256::from(80));
+            assert_eq!(
+                airdrop
+                    .claim_receipt(recipient)
+                    .map(|receipt| receipt.amount),
+                Ok(U256::from(80))
+            );
+            assert_eq!(airdrop.claimed_amount(recipient), U256::from(80));
+        }
+
+        #[ink::test]
+        fn claims_in_range_counts_logged_timestamps() {
+            let mut airdrop = new_airdrop();
+
+            for timestamp in [10, 20, 20, 30] {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
+                airdrop.count_claim();
+            }
+
+            assert_eq!(airdrop.claims_in_range(0, 100), 4);
+            assert_eq!(airdrop.claims_in_range(20, 20), 2);
+            assert_eq!(airdrop.claims_in_range(11, 29), 2);
+            assert_eq!(airdrop.claims_in_range(31, 100), 0);
+            assert_eq!(airdrop.claims_in_range(30, 10), 0);
+        }
+
+        #[ink::test]
+        fn claim_log_keeps_only_the_latest_claims() {
+            let mut airdrop = new_airdrop();
+
+            for timestamp in 0..CLAIM_LOG_SIZE + 10 {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
+                airdrop.count_claim();
+            }
+
+            assert_eq!(airdrop.claims_in_range(0, u64::MAX), CLAIM_LOG_SIZE);
+            assert_eq!(airdrop.claims_in_range(0, 9), 0);
+            assert_eq!(airdrop.claims_in_range(10, 10), 1);
+        }
+
+        #[ink::test]
+        fn empty_proof_only_verifies_a_single_leaf_tree() {
+            let mut airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            let leaf = airdrop.leaf(recipient, U256::from(100));
+            airdrop.root = leaf;
+
+            // Two leaves: a proof is always needed, even if the root is a leaf.
+            assert_eq!(
+                airdrop.check_proof(leaf, 2, leaf, U256::from(100), &[], 0),
+                Err(Error::InvalidProof)
+            );
+
+            assert_eq!(
+                airdrop.check_proof(leaf, 1, leaf, U256::from(100), &[], 0),
+                Ok(())
+            );
+            assert_eq!(
+                airdrop.check_proof([0x33; 32], 1, leaf, U256::from(100), &[], 0),
+                Err(Error::InvalidProof)
+            );
+        }
+
+        #[ink::test]
+        fn claim_multi_pays_every_proven_leaf_once() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            airdrop.num_leaves = 4;
+            let recipient = Address::from([0x22; 20]);
+            let leaves = vec![
+                airdrop.leaf(Address::from([0x33; 20]), U256::from(1)),
+                airdrop.leaf(recipient, U256::from(100)),
+                airdrop.leaf(recipient, U256::from(200)),
+                airdrop.leaf(Address::from([0x44; 20]), U256::from(2)),
+            ];
+            let levels = build_levels(HashAlgo::Keccak256, leaves);
+            airdrop.root = levels.last().unwrap()[0];
+            let (proof, flags) = multiproof_for(&levels, &[1, 2]);
+            let claims = vec![(U256::from(100), 1), (U256::from(200), 2)];
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(
+                airdrop.claim_multi(
+                    vec![(U256::from(100), 1), (U256::from(201), 2)],
+                    proof.clone(),
+                    flags.clone()
+                ),
+                Err(Error::InvalidMultiproof)
+            );
+            assert_eq!(
+                airdrop.claim_multi(claims.clone(), proof.clone(), flags.clone()),
+                Ok(())
+            );
+
+            assert_eq!(airdrop.pending_withdrawal(recipient), U256::from(300));
+            assert!(airdrop.is_claimed_index(1) && airdrop.is_claimed_index(2));
+            assert_eq!(airdrop.claimed_count(), 2);
+            assert_eq!(
+                airdrop.claim_multi(claims.clone(), proof.clone(), flags.clone()),
+                Err(Error::AlreadyClaimed)
+            );
+
+            // Once withdrawn, reversing the claim releases both leaves.
+            airdrop.pending_withdrawals.remove(recipient);
+            airdrop.reserved = U256::zero();
+            ink::env::test::set_caller(Address::from([0x01; 20]));
+            assert_eq!(airdrop.reverse_claim(recipient), Ok(()));
+            assert!(!airdrop.is_claimed_index(1) && !airdrop.is_claimed_index(2));
+            assert_eq!(airdrop.claimed_count(), 0);
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(airdrop.claim_multi(claims, proof, flags), Ok(()));
+        }
+
+        #[ink::test]
+        fn end_time_change_waits_for_the_timelock() {
+            let mut airdrop = new_airdrop_ending_at(10_000);
+            airdrop.end_time_timelock = 1_000;
+
+            assert_eq!(
+                airdrop.queue_end_time_change(1_000),
+                Err(Error::InvalidEndTime)
+            );
+            assert_eq!(airdrop.queue_end_time_change(5_000), Ok(()));
+            assert_eq!(airdrop.pending_end_time(), (5_000, 1_000));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(999);
+            assert_eq!(
+                airdrop.apply_end_time_change(),
+                Err(Error::TimelockNotElapsed)
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(airdrop.apply_end_time_change(), Ok(()));
+            assert_eq!(airdrop.campaign_end_time(), 5_000);
+            assert_eq!(airdrop.pending_end_time(), (0, 0));
+            assert_eq!(airdrop.apply_end_time_change(), Err(Error::InvalidEndTime));
+        }
+
+        #[ink::test]
+        fn sparse_proofs_substitute_zero_subtrees() {
+            let mut airdrop = new_airdrop();
+            airdrop.num_leaves = 8;
+            let algo = HashAlgo::Keccak256;
+
+            let empty = [0u8; 32];
+            let zero_1 = hash(algo, &empty, &empty);
+            let zero_2 = hash(algo, &zero_1, &zero_1);
+            let (leaf_0, leaf_5) = ([0x01; 32], [0x05; 32]);
+            let mut leaves = vec![empty; 8];
+            leaves[0] = leaf_0;
+            leaves[5] = leaf_5;
+            let levels = build_levels(algo, leaves);
+            let root = levels.last().unwrap()[0];
+
+            let proof_0 = [ZERO_SIBLING, ZERO_SIBLING, levels[2][1]];
+            let proof_5 = [ZERO_SIBLING, ZERO_SIBLING, levels[2][0]];
+            assert_eq!(levels[1][1], zero_1);
+            assert_eq!(levels[1][3], zero_1);
+            assert_eq!(
+                airdrop.check_proof(root, 8, leaf_0, U256::from(1), &proof_0, 0),
+                Err(Error::InvalidProof)
+            );
+
+            airdrop.zero_hashes = vec![empty, zero_1, zero_2];
+            assert_eq!(
+                airdrop.check_proof(root, 8, leaf_0, U256::from(1), &proof_0, 0),
+                Ok(())
+            );
+            assert_eq!(
+                airdrop.check_proof(root, 8, leaf_5, U256::from(1), &proof_5, 5),
+                Ok(())
+            );
+            assert_eq!(
+                airdrop.check_proof(root, 8, leaf_5, U256::from(1), &proof_5, 4),
+                Err(Error::InvalidProof)
+            );
+
+            // No zero hash for the top level.
+            airdrop.zero_hashes.truncate(2);
+            assert_eq!(
+                airdrop.check_proof(
+                    root,
+                    8,
+                    leaf_0,
+                    U256::from(1),
+                    &[ZERO_SIBLING, ZERO_SIBLING, ZERO_SIBLING],
+                    0
+                ),
+                Err(Error::InvalidProof)
+            );
+        }
+
+        #[ink::test]
+        fn decimals_factor_can_only_be_fixed_before_claims() {
+            let mut airdrop = new_airdrop();
+
+            assert_eq!(
+                airdrop.set_decimals_factor(U256::zero()),
+                Err(Error::AmountCannotBeZero)
+            );
+            assert_eq!(airdrop.set_decimals_factor(U256::from(1_000)), Ok(()));
+            assert_eq!(airdrop.decimals_factor(), U256::from(1_000));
+
+            // A partial payout, e.g. of a vested nonce entry, counts too.
+            airdrop.pull_payments = true;
+            assert_eq!(
+                airdrop.pay_out(Address::from([0x22; 20]), U256::from(1)),
+                Ok(())
+            );
+            assert_eq!(airdrop.claimed_count(), 0);
+            assert_eq!(
+                airdrop.set_decimals_factor(U256::from(1)),
+                Err(Error::ClaimsAlreadyStarted)
+            );
+        }
+
+        #[ink::test]
+        fn transfers_do_not_nest() {
+            let mut airdrop = new_airdrop();
+            airdrop.transfer_lock.set(&true);
+
+            assert_eq!(
+                airdrop.transfer_out(Address::from([0x22; 20]), U256::from(1)),
+                Err(Error::Reentrant)
+            );
+        }
+
+        #[ink::test]
+        fn retry_claim_requires_an_owed_payout() {
+            let mut airdrop = new_airdrop();
+
+            assert_eq!(airdrop.retry_claim(), Err(Error::NothingOwed));
+            assert_eq!(airdrop.owed(Address::from([0x01; 20])), U256::zero());
+        }
+
+        #[ink::test]
+        fn underfunded_campaign_is_cancelled_at_its_fund_deadline() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            airdrop.fund_deadline = 1_000;
+            airdrop.campaign_start_time = 1_000;
+            airdrop.expected_total = U256::from(10_000);
+            airdrop.total_funded = U256::from(4_000);
+            let recipient = Address::from([0x22; 20]);
+            airdrop.root = airdrop.leaf(recipient, U256::from(100));
+            airdrop.num_leaves = 1;
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(airdrop.finalize_funding(), Err(Error::Unauthorized));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_001);
+            assert_eq!(airdrop.state(), CampaignState::Cancelled);
+            assert_eq!(airdrop.fund(U256::from(6_000)), Err(Error::FundingClosed));
+            assert_eq!(
+                airdrop.claim(U256::from(100), Vec::new(), 0),
+                Err(Error::CampaignCancelled)
+            );
+
+            // Past the deadline, anyone can settle the campaign.
+            assert_eq!(airdrop.finalize_funding(), Ok(()));
+            assert!(airdrop.cancelled);
+        }
+
+        #[ink::test]
+        fn claim_with_sig_only_accepts_signatures_for_this_contract() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            let value = U256::from(100);
+            // Address of the secp256k1 key 0x4c0883a6...3f362318, which signed
+            // `signature` for the contract at 0xc0c0...c0, index 0 and
+            // deadline 5_000.
+            let recipient = Address::from([
+                44, 117, 54, 227, 96, 93, 156, 22, 167, 163, 215, 177, 137, 142, 82, 147, 150, 166,
+                92, 35,
+            ]);
+            let signature = [
+                231, 66, 253, 20, 77, 101, 5, 56, 84, 136, 242, 161, 181, 118, 165, 212, 39, 77,
+                85, 208, 96, 235, 241, 124, 222, 86, 195, 244, 137, 99, 222, 21, 70, 226, 121, 243,
+                197, 253, 199, 225, 29, 176, 249, 240, 133, 32, 144, 121, 16, 99, 48, 231, 98, 198,
+                229, 158, 144, 20, 33, 163, 61, 223, 164, 10, 1,
+            ];
+            airdrop.root = airdrop.leaf(recipient, value);
+            airdrop.num_leaves = 1;
+
+            ink::env::test::set_callee(Address::from([0xc1; 20]));
+            assert_eq!(
+                airdrop.claim_with_sig(recipient, value, Vec::new(), 0, 5_000, signature),
+                Err(Error::InvalidSignature)
+            );
+
+            ink::env::test::set_callee(Address::from([0xc0; 20]));
+            assert_eq!(
+                airdrop.claim_with_sig(recipient, value, Vec::new(), 0, 5_000, signature),
+                Ok(())
+            );
+            assert_eq!(airdrop.pending_withdrawal(recipient), value);
+            assert_eq!(
+                airdrop.claim_with_sig(recipient, value, Vec::new(), 0, 5_000, signature),
+                Err(Error::SignatureAlreadyUsed)
+            );
+        }
+
+        #[cfg(feature = "trace")]
+        #[ink::test]
+        fn trace_proof_reports_every_step() {
+            let mut airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            let value = U256::from(100);
+            let leaf = airdrop.leaf(recipient, value);
+            let sibling = [0x33; 32];
+            airdrop.root = hash(HashAlgo::Keccak256, &sibling, &leaf);
+
+            let trace = airdrop.trace_proof(recipient, value, vec![sibling], 1);
+            assert_eq!(trace.leaf, leaf);
+            assert_eq!(trace.steps, vec![airdrop.root]);
+            assert!(trace.verified);
+
+            let trace = airdrop.trace_proof(recipient, value, vec![sibling], 0);
+            assert_eq!(
+                trace.steps,
+                vec![hash(HashAlgo::Keccak256, &leaf, &sibling)]
+            );
+            assert!(!trace.verified);
+        }
+
+        #[ink::test]
+        fn plain_leaves_stay_untagged() {
+            let airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            let value = U256::from(100);
+
+            let packed = [recipient.as_bytes(), &value.to_big_endian()[..]].concat();
+            let mut expected = <Keccak256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Keccak256>(&packed, &mut expected);
+            assert_eq!(airdrop.leaf_of(recipient, value), expected);
+        }
+
+        #[ink::test]
+        fn escrow_proof_does_not_verify_as_a_nonce_claim() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            let recipient = Address::from([0x22; 20]);
+            airdrop.root = airdrop.escrow_leaf(recipient, U256::from(100), 5_000);
+            airdrop.num_leaves = 1;
+            ink::env::test::set_caller(recipient);
+
+            // Nonce 0 and this value lay out the same 40 bytes after the
+            // address as the escrow leaf; only the leaf type tag differs.
+            let value = U256::from(100) * U256::from(1u128 << 64) + U256::from(5_000);
+            assert_eq!(
+                airdrop.claim_with_nonce(value, 0, Vec::new(), 0),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                airdrop.claim_to_escrow(U256::from(100), Vec::new(), 0, 5_000),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn sweep_and_transfer_ownership_waits_for_the_sweep_delay() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+            airdrop.sweep_delay = 500;
+            let new_owner = Address::from([0x22; 20]);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_200);
+            assert_eq!(
+                airdrop.sweep_and_transfer_ownership(new_owner),
+                Err(Error::ClaimPeriodActive)
+            );
+            assert_eq!(airdrop.owner(), Address::from([0x01; 20]));
+
+            // Past the delay, an under-claimed campaign extends instead.
+            airdrop.auto_extend_threshold_bps = 5_000;
+            airdrop.auto_extend_duration = 1_000;
+            airdrop.expected_total = U256::from(1_000);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_501);
+            assert_eq!(airdrop.sweep_and_transfer_ownership(new_owner), Ok(()));
+            assert_eq!(airdrop.campaign_end_time(), 2_501);
+            assert_eq!(airdrop.owner(), Address::from([0x01; 20]));
+        }
+
+        #[ink::test]
+        fn receipt_holds_the_amount_paid_after_the_fee() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            airdrop.max_fee_bps = 100;
+            airdrop.fee_bps = 100;
+            let recipient = Address::from([0x22; 20]);
+            airdrop.root = airdrop.leaf(recipient, U256::from(1_000));
+            airdrop.num_leaves = 1;
+            ink::env::test::set_caller(recipient);
+
+            assert_eq!(airdrop.claim(U256::from(1_000), Vec::new(), 0), Ok(()));
+            assert_eq!(
+                airdrop
+                    .claim_receipt(recipient)
+                    .map(|receipt| receipt.amount),
+                Ok(U256::from(990))
+            );
+            assert_eq!(airdrop.claimed_amount(recipient), U256::from(990));
+            assert_eq!(airdrop.total_claimed(), U256::from(1_000));
+        }
+
+        #[ink::test]
+        fn reversal_waits_for_the_payout_and_undoes_the_claim() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            airdrop.max_fee_bps = 100;
+            airdrop.fee_bps = 100;
+            airdrop.claim_cooldown = 1_000;
+            let recipient = Address::from([0x22; 20]);
+            airdrop.root = airdrop.leaf(recipient, U256::from(1_000));
+            airdrop.num_leaves = 1;
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(airdrop.claim(U256::from(1_000), Vec::new(), 0), Ok(()));
+
+            ink::env::test::set_caller(Address::from([0x01; 20]));
+            assert_eq!(
+                airdrop.reverse_claim(recipient),
+                Err(Error::PayoutOutstanding)
+            );
+
+            // As `withdraw` leaves it once the payout is transferred.
+            airdrop.pending_withdrawals.remove(recipient);
+            airdrop.reserved = U256::zero();
+            assert_eq!(airdrop.reverse_claim(recipient), Ok(()));
+            assert_eq!(airdrop.total_claimed(), U256::zero());
+            assert_eq!(airdrop.claimed_amount(recipient), U256::zero());
+            assert_eq!(airdrop.next_claim_available(recipient), 0);
+            assert_eq!(airdrop.claim_receipt(recipient), Err(Error::NotClaimed));
+
+            ink::env::test::set_caller(recipient);
+            assert_eq!(airdrop.claim(U256::from(1_000), Vec::new(), 0), Ok(()));
+            assert_eq!(airdrop.total_claimed(), U256::from(1_000));
+            assert_eq!(airdrop.pending_withdrawal(recipient), U256::from(990));
+        }
+
+        #[ink::test]
+        fn why_invalid_reports_payout_checks_after_the_proof() {
+            let mut airdrop = new_airdrop();
+            let recipient = Address::from([0x22; 20]);
+            let value = U256::from(100);
+            airdrop.root = airdrop.leaf(recipient, value);
+            airdrop.num_leaves = 1;
+            let why =
+                |airdrop: &MerkleAirdrop| airdrop.why_invalid(recipient, value, Vec::new(), 0);
+            assert_eq!(why(&airdrop), RejectionReason::Valid);
+
+            airdrop.paused = true;
+            assert_eq!(why(&airdrop), RejectionReason::Paused);
+            airdrop.paused = false;
+
+            airdrop.claims_enabled = false;
+            assert_eq!(why(&airdrop), RejectionReason::ClaimsDisabled);
+            airdrop.claims_enabled = true;
+
+            airdrop.claim_cooldown = 100;
+            airdrop.last_payout_at.insert(recipient, &0);
+            assert_eq!(why(&airdrop), RejectionReason::Cooldown);
+            airdrop.last_payout_at.remove(recipient);
+
+            airdrop.cancelled = true;
+            assert_eq!(why(&airdrop), RejectionReason::Cancelled);
+        }
+
+        #[ink::test]
+        fn shard_claims_follow_the_main_tree_rules() {
+            let mut airdrop = new_airdrop();
+            airdrop.pull_payments = true;
+            let recipient = Address::from([0x22; 20]);
+            let leaf = airdrop.leaf(recipient, U256::from(100));
+            airdrop.shard_roots = vec![(leaf, 2), (leaf, 1)];
+            ink::env::test::set_caller(recipient);
+
+            // The first shard has two leaves, so an empty proof is refused
+            // there whatever the main tree's size.
+            airdrop.num_leaves = 1;
+            assert_eq!(
+                airdrop.claim_from_tree(0, U256::from(100), Vec::new(), 0),
+                Err(Error::InvalidProof)
+            );
+
+            airdrop.num_leaves = 2;
+            assert_eq!(
+                airdrop.claim_from_tree(1, U256::from(100), Vec::new(), 1),
+                Err(Error::IndexOutOfRange)
+            );
+            assert_eq!(
+                airdrop.claim_from_tree(1, U256::from(100), Vec::new(), 0),
+                Ok(())
+            );
+            assert!(airdrop.is_claimed_in_tree(1, recipient));
+            assert!(!airdrop.is_claimed(recipient));
+            assert_eq!(airdrop.claimed_count(), 0);
+            assert_eq!(
+                airdrop.tree_claim_receipt(1, recipient),
+                Ok(ClaimReceipt {
+                    amount: U256::from(100),
+                    timestamp: 0,
+                    index: 0,
+                    withheld: U256::zero(),
+                })
+            );
+            assert_eq!(airdrop.claim_receipt(recipient), Err(Error::NotClaimed));
+        }
+
+        #[ink::test]
+        fn root_provider_campaigns_cannot_replace_the_root() {
+            let mut airdrop = new_airdrop();
+            airdrop.root_provider = Address::from([0x33; 20]);
+
+            assert_eq!(
+                airdrop.update_root([0x22; 32], 2),
+                Err(Error::RootProviderActive)
+            );
+            assert_eq!(airdrop.root(), [0x11; 32]);
+        }
+
+        #[ink::test]
+        fn shorten_campaign_respects_the_end_time_timelock() {
+            let mut airdrop = new_airdrop_ending_at(10_000);
+
+            assert_eq!(airdrop.shorten_campaign(10_000), Err(Error::InvalidEndTime));
+            assert_eq!(airdrop.shorten_campaign(8_000), Ok(()));
+            assert_eq!(airdrop.campaign_end_time, 8_000);
+
+            airdrop.end_time_timelock = 1_000;
+            assert_eq!(airdrop.shorten_campaign(500), Err(Error::InvalidEndTime));
+            assert_eq!(airdrop.shorten_campaign(5_000), Ok(()));
+            assert_eq!(airdrop.campaign_end_time, 8_000);
+            assert_eq!(airdrop.pending_end_time, 5_000);
+
+            assert_eq!(
+                airdrop.apply_end_time_change(),
+                Err(Error::TimelockNotElapsed)
+            );
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(airdrop.apply_end_time_change(), Ok(()));
+            assert_eq!(airdrop.campaign_end_time, 5_000);
+        }
+
+        #[ink::test]
+        fn claim_with_nonce_pays_what_claim_all_vested_left() {
+            let mut airdrop = new_airdrop_ending_at(1_000);
+            airdrop.pull_payments = true;
+            let recipient = Address::from([0x01; 20]);
+            airdrop.root = airdrop.nonce_leaf(recipient, 7, U256::from(100));
+            airdrop.num_leaves = 1;
+            let entry = NonceClaim {
+                value: U256::from(100),
+                nonce: 7,
+                proof: Vec::new(),
+                index: 0,
+            };
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(400);
+            assert_eq!(airdrop.claim_all_vested(vec![entry]), Ok(U256::from(40)));
+            assert_eq!(
+                airdrop.claim_with_nonce(U256::from(100), 7, Vec::new(), 0),
+                Ok(())
+            );
+            assert_eq!(airdrop.pending_withdrawal(recipient), U256::from(100));
+            assert!(airdrop.is_claimed_nonce(recipient, 7));
+        }
     }
 }
 
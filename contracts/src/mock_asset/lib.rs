@@ -0,0 +1,217 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// # Mock Asset Contract
+///
+/// Minimal ERC20 used by the Merkle airdrop e2e tests in place of the Asset
+/// Hub precompile. Its `transfer` can be armed to call back into an airdrop's
+/// `claim_for` once, to check that a token with transfer hooks cannot make
+/// the airdrop pay the same leaf twice, and can refuse to pay chosen
+/// recipients, like a token with its own blocklist. It can also return
+/// nothing from `transfer`, like tokens written before ERC20 required a
+/// return value.
+pub use self::mock_asset::*;
+
+#[ink::contract]
+pub mod mock_asset {
+    use assets::asset_hub_precompile::Erc20;
+    use assets::Error;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::{CallFlags, ReturnFlags};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use ink::U256;
+
+    /// `reentry_error` value when the call back returned no airdrop error.
+    pub const NO_ERROR: u8 = u8::MAX;
+
+    /// ERC20 balances plus the claim to replay from inside `transfer`.
+    #[ink(storage)]
+    pub struct MockAsset {
+        total_supply: U256,
+        balances: Mapping<Address, U256>,
+        allowances: Mapping<(Address, Address), U256>,
+        /// Recipients `transfer` refuses to pay.
+        blocked: Mapping<Address, bool>,
+        /// Whether `transfer` returns nothing, reverting instead of returning
+        /// `false` when it refuses.
+        silent: bool,
+        /// Airdrop to call back into on the next `transfer`, if armed.
+        reentry_target: Option<Address>,
+        reentry_recipient: Address,
+        reentry_value: U256,
+        reentry_proof: Vec<[u8; 32]>,
+        reentry_index: u64,
+        reentry_attempted: bool,
+        reentry_error: u8,
+    }
+
+    impl MockAsset {
+        /// Mint `total_supply` tokens to the caller.
+        #[ink(constructor)]
+        pub fn new(total_supply: U256) -> Self {
+            let mut balances = Mapping::new();
+            balances.insert(Self::env().caller(), &total_supply);
+
+            Self {
+                total_supply,
+                balances,
+                allowances: Mapping::new(),
+                blocked: Mapping::new(),
+                silent: false,
+                reentry_target: None,
+                reentry_recipient: Address::zero(),
+                reentry_value: U256::zero(),
+                reentry_proof: Vec::new(),
+                reentry_index: 0,
+                reentry_attempted: false,
+                reentry_error: NO_ERROR,
+            }
+        }
+
+        /// Make the next `transfer` call `target.claim_for(recipient, value,
+        /// proof, index)` before returning.
+        #[ink(message)]
+        pub fn arm_reentry(
+            &mut self,
+            target: Address,
+            recipient: Address,
+            value: U256,
+            proof: Vec<[u8; 32]>,
+            index: u64,
+        ) {
+            self.reentry_target = Some(target);
+            self.reentry_recipient = recipient;
+            self.reentry_value = value;
+            self.reentry_proof = proof;
+            self.reentry_index = index;
+        }
+
+        /// Make `transfer` return `false` for transfers to `account`, or stop
+        /// doing so.
+        #[ink(message)]
+        pub fn set_blocked(&mut self, account: Address, blocked: bool) {
+            self.blocked.insert(account, &blocked);
+        }
+
+        /// Make `transfer` return nothing, or go back to returning a `bool`.
+        #[ink(message)]
+        pub fn set_silent(&mut self, silent: bool) {
+            self.silent = silent;
+        }
+
+        /// Whether an armed `transfer` has tried to call back.
+        #[ink(message)]
+        pub fn reentry_attempted(&self) -> bool {
+            self.reentry_attempted
+        }
+
+        /// Error code the airdrop returned to the call back, or `NO_ERROR`
+        /// if it succeeded or failed without returning one.
+        #[ink(message)]
+        pub fn reentry_error(&self) -> u8 {
+            self.reentry_error
+        }
+
+        /// Move `value` from `from` to `to`, reporting `false` rather than
+        /// reverting on insufficient balance.
+        fn move_balance(&mut self, from: Address, to: Address, value: U256) -> bool {
+            let from_balance = self.balanceOf(from);
+            if from_balance < value {
+                return false;
+            }
+
+            self.balances.insert(from, &(from_balance - value));
+            let to_balance = self.balanceOf(to);
+            self.balances.insert(to, &to_balance.saturating_add(value));
+
+            true
+        }
+
+        /// Call back into the armed airdrop once, recording the outcome.
+        fn reenter(&mut self) {
+            let Some(target) = self.reentry_target.take() else {
+                return;
+            };
+            self.reentry_attempted = true;
+
+            // The airdrop's error type is not visible here; its variants
+            // encode as a single byte, so `u8` decodes any of them. Re-entry
+            // has to be allowed explicitly, or the runtime refuses the call
+            // before the airdrop's own checks run.
+            let result = build_call::<ink::env::DefaultEnvironment>()
+                .call(target)
+                .call_flags(CallFlags::ALLOW_REENTRY)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("claim_for")))
+                        .push_arg(self.reentry_recipient)
+                        .push_arg(self.reentry_value)
+                        .push_arg(self.reentry_proof.clone())
+                        .push_arg(self.reentry_index),
+                )
+                .returns::<core::result::Result<(), u8>>()
+                .try_invoke();
+
+            if let Ok(Ok(Err(code))) = result {
+                self.reentry_error = code;
+            }
+        }
+    }
+
+    impl Erc20 for MockAsset {
+        #[ink(message)]
+        fn totalSupply(&self) -> U256 {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balanceOf(&self, account: Address) -> U256 {
+            self.balances.get(account).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: Address, value: U256) -> Result<bool, Error> {
+            let caller = self.env().caller();
+            let refused =
+                self.blocked.get(to).unwrap_or_default() || !self.move_balance(caller, to, value);
+            if self.silent {
+                assert!(!refused, "transfer refused");
+                // Balances live in a `Mapping`, written as they change, so
+                // returning early loses nothing.
+                ink::env::return_value::<()>(ReturnFlags::empty(), &());
+            }
+            if refused {
+                return Ok(false);
+            }
+
+            self.reenter();
+
+            Ok(true)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: Address, spender: Address) -> U256 {
+            self.allowances.get((owner, spender)).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: Address, value: U256) -> Result<bool, Error> {
+            let caller = self.env().caller();
+            self.allowances.insert((caller, spender), &value);
+
+            Ok(true)
+        }
+
+        #[ink(message)]
+        fn transferFrom(&mut self, from: Address, to: Address, value: U256) -> Result<bool, Error> {
+            let caller = self.env().caller();
+            let allowance = self.allowance(from, caller);
+            if allowance < value || !self.move_balance(from, to, value) {
+                return Ok(false);
+            }
+
+            self.allowances.insert((from, caller), &(allowance - value));
+
+            Ok(true)
+        }
+    }
+}
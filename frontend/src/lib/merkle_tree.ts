@@ -12,6 +12,19 @@ export type LeafData = {
   value: bigint
 }
 
+/**
+ * @notice Leaf type tags, the first byte of every tagged leaf preimage.
+ * @dev Mirrors the `LEAF_*` constants of the contract. Plain (address, uint256)
+ * leaves carry no tag; see `encodeLeaf`.
+ */
+export enum LeafTag {
+  Units = 0x01,
+  Share = 0x02,
+  Nonce = 0x03,
+  Escrow = 0x04,
+  MultiAsset = 0x05,
+}
+
 /**
  * @title MerkleTree
  * @notice Utility class for building and verifying Merkle proofs.
@@ -49,6 +62,25 @@ export class MerkleTree {
     return getBytes(keccak_256(encoded))
   }
 
+  /**
+   * @notice Encodes a leaf of any kind as `keccak256(tag || address || data)`.
+   * @dev Matches the contract's leaf type tags, so e.g. an escrow leaf can
+   * never be replayed as a nonce leaf. None of the contract's tagged leaf kinds
+   * has a 52-byte preimage, so none collides with an `encodeLeaf` leaf.
+   * @param tag Leaf type tag, one of `LeafTag`.
+   * @param recipient address.
+   * @param data Rest of the preimage, e.g. `nonce (8 bytes) || value (32 bytes)`.
+   * @return Encoded and hashed leaf (32 bytes).
+   */
+  public static encodeTaggedLeaf(tag: LeafTag, recipient: string, data: Uint8Array): Uint8Array {
+    const addr = getBytes(recipient) // 20 bytes
+    const encoded = new Uint8Array(1 + addr.length + data.length)
+    encoded[0] = tag
+    encoded.set(addr, 1)
+    encoded.set(data, 1 + addr.length)
+    return getBytes(keccak_256(encoded))
+  }
+
   /**
    * @notice Hashes two child nodes into a parent node.
    * @dev Computes `keccak256(left || right)`.